@@ -77,17 +77,7 @@ fn main() -> anyhow::Result<()> {
     println!("\n=== Suspicious Strings Only ===");
     let suspicious_filter = StringFilter {
         suspicious_only: Some(true),
-        min_occurrences: None,
-        max_occurrences: None,
-        min_length: None,
-        max_length: None,
-        categories: None,
-        file_paths: None,
-        file_hashes: None,
-        regex_pattern: None,
-        min_entropy: None,
-        max_entropy: None,
-        date_range: None,
+        ..Default::default()
     };
 
     let suspicious_stats = tracker.get_statistics(Some(&suspicious_filter));
@@ -108,17 +98,7 @@ fn main() -> anyhow::Result<()> {
     println!("\n=== Searching for Command-Related Strings ===");
     let command_filter = StringFilter {
         categories: Some(vec!["command".to_string()]),
-        suspicious_only: None,
-        min_occurrences: None,
-        max_occurrences: None,
-        min_length: None,
-        max_length: None,
-        file_paths: None,
-        file_hashes: None,
-        regex_pattern: None,
-        min_entropy: None,
-        max_entropy: None,
-        date_range: None,
+        ..Default::default()
     };
 
     let command_stats = tracker.get_statistics(Some(&command_filter));
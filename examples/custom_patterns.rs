@@ -133,17 +133,7 @@ fn main() -> anyhow::Result<()> {
     println!("\n=== Cryptocurrency Indicators ===");
     let crypto_filter = threatflux_string_analysis::StringFilter {
         categories: Some(vec!["cryptocurrency".to_string(), "mining".to_string()]),
-        suspicious_only: None,
-        min_occurrences: None,
-        max_occurrences: None,
-        min_length: None,
-        max_length: None,
-        file_paths: None,
-        file_hashes: None,
-        regex_pattern: None,
-        min_entropy: None,
-        max_entropy: None,
-        date_range: None,
+        ..Default::default()
     };
 
     let crypto_stats = tracker.get_statistics(Some(&crypto_filter));
@@ -157,17 +147,7 @@ fn main() -> anyhow::Result<()> {
     println!("\n=== Ransomware Indicators ===");
     let ransomware_filter = threatflux_string_analysis::StringFilter {
         categories: Some(vec!["ransomware".to_string()]),
-        suspicious_only: None,
-        min_occurrences: None,
-        max_occurrences: None,
-        min_length: None,
-        max_length: None,
-        file_paths: None,
-        file_hashes: None,
-        regex_pattern: None,
-        min_entropy: None,
-        max_entropy: None,
-        date_range: None,
+        ..Default::default()
     };
 
     let ransomware_stats = tracker.get_statistics(Some(&ransomware_filter));
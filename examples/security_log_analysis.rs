@@ -108,17 +108,7 @@ fn main() -> anyhow::Result<()> {
     println!("\n=== Suspicious Indicators ===");
     let suspicious_filter = StringFilter {
         suspicious_only: Some(true),
-        min_occurrences: None,
-        max_occurrences: None,
-        min_length: None,
-        max_length: None,
-        categories: None,
-        file_paths: None,
-        file_hashes: None,
-        regex_pattern: None,
-        min_entropy: None,
-        max_entropy: None,
-        date_range: None,
+        ..Default::default()
     };
 
     let suspicious_stats = tracker.get_statistics(Some(&suspicious_filter));
@@ -137,17 +127,7 @@ fn main() -> anyhow::Result<()> {
     println!("\n=== Repeated Patterns (Potential Attacks) ===");
     let repeated_filter = StringFilter {
         min_occurrences: Some(3),
-        max_occurrences: None,
-        min_length: None,
-        max_length: None,
-        categories: None,
-        file_paths: None,
-        file_hashes: None,
-        suspicious_only: None,
-        regex_pattern: None,
-        min_entropy: None,
-        max_entropy: None,
-        date_range: None,
+        ..Default::default()
     };
 
     let repeated_stats = tracker.get_statistics(Some(&repeated_filter));
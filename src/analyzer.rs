@@ -21,8 +21,13 @@ pub struct SuspiciousIndicator {
 /// Result of analyzing a string
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StringAnalysis {
-    /// Shannon entropy of the string
+    /// Shannon entropy over the string's characters
+    ///
+    /// Equals `byte_entropy` for ASCII; for multibyte text it is computed per character.
     pub entropy: f64,
+    /// Shannon entropy over the string's UTF-8 bytes
+    #[serde(default)]
+    pub byte_entropy: f64,
     /// Categories the string belongs to
     pub categories: HashSet<String>,
     /// Suspicious indicators found
@@ -31,6 +36,9 @@ pub struct StringAnalysis {
     pub metadata: StringMetadata,
     /// Whether the string is considered suspicious overall
     pub is_suspicious: bool,
+    /// Whether indicator collection stopped early at the analyzer's match limit
+    #[serde(default)]
+    pub indicators_truncated: bool,
 }
 
 /// Trait for analyzing strings
@@ -46,6 +54,13 @@ pub trait StringAnalyzer: Send + Sync {
     /// Calculate entropy of a string
     fn calculate_entropy(&self, value: &str) -> f64;
 
+    /// Calculate Shannon entropy over the UTF-8 bytes of a string
+    ///
+    /// Equals the character entropy for ASCII, but differs for multibyte text.
+    fn calculate_byte_entropy(&self, value: &str) -> f64 {
+        byte_entropy(value.as_bytes())
+    }
+
     /// Get the patterns used by this analyzer
     fn get_patterns(&self) -> &[Pattern];
 
@@ -53,10 +68,43 @@ pub trait StringAnalyzer: Send + Sync {
     fn add_pattern(&mut self, pattern: Pattern) -> AnalysisResult<()>;
 }
 
+/// Trait for estimating the randomness of a string
+///
+/// The estimate is stored as a string's `entropy` and compared against the analyzer's
+/// entropy threshold.
+pub trait EntropyEstimator: Send + Sync {
+    /// Estimate the entropy of a string
+    fn estimate(&self, value: &str) -> f64;
+}
+
+/// Shannon entropy over the characters of a string, in bits per character
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShannonEntropy;
+
+impl EntropyEstimator for ShannonEntropy {
+    fn estimate(&self, value: &str) -> f64 {
+        if value.is_ascii() {
+            // Characters and bytes coincide, so use the cheaper byte histogram
+            return byte_entropy(value.as_bytes());
+        }
+
+        let mut char_counts: HashMap<char, u32> = HashMap::new();
+        for c in value.chars() {
+            *char_counts.entry(c).or_insert(0) += 1;
+        }
+
+        let len = char_counts.values().sum::<u32>() as f64;
+        shannon_entropy(char_counts.into_values(), len)
+    }
+}
+
 /// Default implementation of StringAnalyzer
 pub struct DefaultStringAnalyzer {
     patterns: Vec<Pattern>,
+    entropy_estimator: Box<dyn EntropyEstimator>,
     entropy_threshold: f64,
+    max_matches_per_string: Option<usize>,
+    nonprintable_ratio_threshold: f64,
 }
 
 impl DefaultStringAnalyzer {
@@ -64,39 +112,85 @@ impl DefaultStringAnalyzer {
     pub fn new() -> Self {
         Self {
             patterns: Vec::new(),
+            entropy_estimator: Box::new(ShannonEntropy),
             entropy_threshold: 4.5,
+            max_matches_per_string: None,
+            nonprintable_ratio_threshold: 0.0,
         }
     }
 
     /// Set the entropy threshold for suspicious detection
-    #[allow(dead_code)]
     pub fn with_entropy_threshold(mut self, threshold: f64) -> Self {
         self.entropy_threshold = threshold;
         self
     }
 
+    /// Replace the entropy estimator, which defaults to [`ShannonEntropy`]
+    pub fn with_entropy_estimator(mut self, estimator: Box<dyn EntropyEstimator>) -> Self {
+        self.entropy_estimator = estimator;
+        self
+    }
+
+    /// Set the fraction of non-printable characters required to flag a string
+    ///
+    /// The default of 0.0 flags any string containing at least one non-printable
+    /// character (other than `\n`, `\r` and `\t`).
+    pub fn with_nonprintable_ratio_threshold(mut self, threshold: f64) -> Self {
+        self.nonprintable_ratio_threshold = threshold;
+        self
+    }
+
+    /// Stop collecting suspicious indicators for a string once `max` have been found
+    ///
+    /// Bounds the work done on strings that match many patterns. Analyses that hit the
+    /// limit have `indicators_truncated` set.
+    pub fn with_max_matches_per_string(mut self, max: usize) -> Self {
+        self.max_matches_per_string = Some(max);
+        self
+    }
+
     /// Add patterns to the analyzer
     pub fn with_patterns(mut self, patterns: Vec<Pattern>) -> Self {
         self.patterns = patterns;
         self
     }
+
+    /// Extract the text reported for a pattern match, preferring a named capture group
+    fn matched_text(pattern: &Pattern, value: &str) -> Option<String> {
+        let Some(name) = pattern.regex.capture_names().flatten().next() else {
+            return pattern.regex.find(value).map(|m| m.as_str().to_string());
+        };
+
+        let captures = pattern.regex.captures(value)?;
+        captures
+            .name(name)
+            .or_else(|| captures.get(0))
+            .map(|m| m.as_str().to_string())
+    }
 }
 
 impl StringAnalyzer for DefaultStringAnalyzer {
     fn analyze(&self, value: &str) -> StringAnalysis {
         let entropy = self.calculate_entropy(value);
+        let byte_entropy = self.calculate_byte_entropy(value);
         let mut suspicious_indicators = Vec::new();
         let mut categories = HashSet::new();
+        let max_matches = self.max_matches_per_string.unwrap_or(usize::MAX);
+        let mut indicators_truncated = false;
 
         // Check against patterns
         for pattern in &self.patterns {
             if pattern.regex.is_match(value) {
+                if pattern.is_suspicious && suspicious_indicators.len() >= max_matches {
+                    indicators_truncated = true;
+                    break;
+                }
                 if pattern.is_suspicious {
                     suspicious_indicators.push(SuspiciousIndicator {
                         pattern_name: pattern.name.clone(),
                         description: pattern.description.clone(),
                         severity: pattern.severity,
-                        matched_text: pattern.regex.find(value).map(|m| m.as_str().to_string()),
+                        matched_text: Self::matched_text(pattern, value),
                     });
                 }
                 categories.insert(pattern.category.clone());
@@ -105,7 +199,9 @@ impl StringAnalyzer for DefaultStringAnalyzer {
 
         // Check entropy
         let high_entropy = entropy > self.entropy_threshold && value.len() > 10;
-        if high_entropy {
+        if high_entropy && suspicious_indicators.len() >= max_matches {
+            indicators_truncated = true;
+        } else if high_entropy {
             suspicious_indicators.push(SuspiciousIndicator {
                 pattern_name: "high_entropy".to_string(),
                 description: format!(
@@ -118,10 +214,16 @@ impl StringAnalyzer for DefaultStringAnalyzer {
         }
 
         // Check for non-printable characters
-        let has_non_printable = value
+        let non_printable = value
             .chars()
-            .any(|c| c.is_control() && c != '\n' && c != '\r' && c != '\t');
-        if has_non_printable {
+            .filter(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+            .count();
+        let has_non_printable = non_printable > 0
+            && non_printable as f64 / value.chars().count() as f64
+                >= self.nonprintable_ratio_threshold;
+        if has_non_printable && suspicious_indicators.len() >= max_matches {
+            indicators_truncated = true;
+        } else if has_non_printable {
             suspicious_indicators.push(SuspiciousIndicator {
                 pattern_name: "non_printable_chars".to_string(),
                 description: "Contains non-printable characters".to_string(),
@@ -134,40 +236,17 @@ impl StringAnalyzer for DefaultStringAnalyzer {
 
         StringAnalysis {
             entropy,
+            byte_entropy,
             categories,
             suspicious_indicators,
             metadata: HashMap::new(),
             is_suspicious,
+            indicators_truncated,
         }
     }
 
     fn calculate_entropy(&self, s: &str) -> f64 {
-        if s.is_empty() {
-            return 0.0;
-        }
-
-        // Use a fixed-size array for byte-based entropy calculation which is more efficient
-        // for ASCII strings and provides similar results
-        let mut byte_counts = [0u32; 256];
-        let bytes = s.as_bytes();
-
-        // Count byte frequencies
-        for &byte in bytes {
-            byte_counts[byte as usize] += 1;
-        }
-
-        let len = bytes.len() as f64;
-        let mut entropy = 0.0;
-
-        // Calculate entropy based on byte frequencies
-        for &count in &byte_counts {
-            if count > 0 {
-                let probability = count as f64 / len;
-                entropy -= probability * probability.log2();
-            }
-        }
-
-        entropy
+        self.entropy_estimator.estimate(s)
     }
 
     fn get_patterns(&self) -> &[Pattern] {
@@ -180,6 +259,32 @@ impl StringAnalyzer for DefaultStringAnalyzer {
     }
 }
 
+/// Shannon entropy of a byte sequence
+fn byte_entropy(bytes: &[u8]) -> f64 {
+    // A fixed-size array is cheaper than a map for byte frequencies
+    let mut byte_counts = [0u32; 256];
+    for &byte in bytes {
+        byte_counts[byte as usize] += 1;
+    }
+
+    shannon_entropy(byte_counts.into_iter(), bytes.len() as f64)
+}
+
+/// Shannon entropy from symbol counts summing to `len`
+fn shannon_entropy(counts: impl Iterator<Item = u32>, len: f64) -> f64 {
+    if len == 0.0 {
+        return 0.0;
+    }
+
+    counts
+        .filter(|&count| count > 0)
+        .map(|count| {
+            let probability = count as f64 / len;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
 impl Default for DefaultStringAnalyzer {
     fn default() -> Self {
         Self::new()
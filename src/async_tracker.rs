@@ -0,0 +1,113 @@
+//! Async string tracking for tokio runtimes
+
+use crate::error::StringAnalysisError;
+use crate::tracker::{
+    CompiledExpr, EntryStore, StringContext, StringEntry, StringFilter, StringOccurrence,
+    StringStatistics, StringTracker,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Async counterpart of [`StringTracker`] backed by a `tokio::sync::RwLock`
+///
+/// Waiting for the entries lock yields to the runtime instead of blocking a worker
+/// thread. The analyzer and categorizer are still CPU-bound and run synchronously inside
+/// `track_string` the first time a string is seen, so very expensive custom analyzers may
+/// still warrant `spawn_blocking`.
+#[derive(Clone)]
+pub struct AsyncStringTracker {
+    tracker: StringTracker,
+    entries: Arc<RwLock<EntryStore>>,
+}
+
+impl Default for AsyncStringTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AsyncStringTracker {
+    /// Create an async tracker with the default analyzer and categorizer
+    pub fn new() -> Self {
+        Self::from_tracker(StringTracker::new())
+    }
+
+    /// Create an async tracker using the components and settings of `tracker`
+    ///
+    /// Strings already tracked by `tracker` are moved into the async tracker.
+    pub fn from_tracker(tracker: StringTracker) -> Self {
+        let entries = tracker.take_entries();
+        Self {
+            tracker,
+            entries: Arc::new(RwLock::new(entries)),
+        }
+    }
+
+    /// Track a string occurrence
+    pub async fn track_string(
+        &self,
+        value: &str,
+        file_path: &str,
+        file_hash: &str,
+        tool_name: &str,
+        context: StringContext,
+    ) -> Result<(), StringAnalysisError> {
+        if self.tracker.is_ignored(value) {
+            return Ok(());
+        }
+
+        let occurrence = StringOccurrence {
+            file_path: file_path.to_string(),
+            file_hash: file_hash.to_string(),
+            tool_name: tool_name.to_string(),
+            timestamp: Utc::now(),
+            context,
+        };
+
+        let new_suspicious = {
+            let mut entries = self.entries.write().await;
+            self.tracker
+                .record_occurrence(&mut entries, value, occurrence)
+        };
+        if let Some(entry) = new_suspicious {
+            self.tracker.notify_new_suspicious(&entry);
+        }
+        Ok(())
+    }
+
+    /// Get statistics about tracked strings
+    ///
+    /// A filter whose `regex_pattern` does not compile yields empty statistics, which
+    /// cannot be told apart from a filter that matched nothing. Use
+    /// [`try_get_statistics`](Self::try_get_statistics) or [`StringFilter::validate`]
+    /// when the filter comes from user input.
+    pub async fn get_statistics(&self, filter: Option<&StringFilter>) -> StringStatistics {
+        self.try_get_statistics(filter).await.unwrap_or_default()
+    }
+
+    /// Get statistics about tracked strings, failing if the filter is invalid
+    pub async fn try_get_statistics(
+        &self,
+        filter: Option<&StringFilter>,
+    ) -> Result<StringStatistics, StringAnalysisError> {
+        let filter = CompiledExpr::compile(filter)?;
+        let entries = self.entries.read().await;
+        Ok(self.tracker.compute_statistics(&entries, filter.as_ref()))
+    }
+
+    /// Search for strings matching a query
+    pub async fn search_strings(&self, query: &str, limit: usize) -> Vec<StringEntry> {
+        let entries = self.entries.read().await;
+        StringTracker::search_entries(&entries, query, limit)
+    }
+
+    /// Get detailed information about a specific string
+    pub async fn get_string_details(&self, value: &str) -> Option<StringEntry> {
+        self.entries
+            .read()
+            .await
+            .get(self.tracker.entry_key(value).as_ref())
+            .cloned()
+    }
+}
@@ -15,6 +15,26 @@ static IPV4_REGEX: Lazy<Regex> =
 static IPV6_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^([0-9a-fA-F]{1,4}:){1,7}[0-9a-fA-F]{1,4}$|^::1$|^::$").unwrap());
 
+static UNC_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\\\\[^\\/]+\\[^\\/]+").unwrap());
+
+static ADMIN_SHARE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^\\\\[^\\/]+\\([A-Z]|ADMIN|IPC)\$(\\|$)").unwrap());
+
+static HASH_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:[0-9a-fA-F]{32}|[0-9a-fA-F]{40}|[0-9a-fA-F]{64})$").unwrap());
+
+static CRYPTO_WALLET_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(?:[13][a-km-zA-HJ-NP-Z1-9]{25,34}|bc1[ac-hj-np-z02-9]{11,71}|0x[0-9a-fA-F]{40})$",
+    )
+    .unwrap()
+});
+
+static UUID_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$")
+        .unwrap()
+});
+
 static EMAIL_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap());
 
@@ -59,12 +79,16 @@ pub trait Categorizer: Send + Sync {
 /// Default categorizer implementation
 pub struct DefaultCategorizer {
     rules: Vec<CategoryRule>,
+    script_detection: bool,
 }
 
 impl DefaultCategorizer {
     /// Create a new categorizer with default rules
     pub fn new() -> Self {
-        let mut categorizer = Self { rules: Vec::new() };
+        let mut categorizer = Self {
+            rules: Vec::new(),
+            script_detection: true,
+        };
 
         // Add default rules
         categorizer.add_default_rules();
@@ -75,7 +99,55 @@ impl DefaultCategorizer {
     /// Create an empty categorizer
     #[allow(dead_code)]
     pub fn empty() -> Self {
-        Self { rules: Vec::new() }
+        Self {
+            rules: Vec::new(),
+            script_detection: false,
+        }
+    }
+
+    /// Enable or disable Unicode script categories (`script:<name>`, `mixed_script`)
+    pub fn with_script_detection(mut self, enabled: bool) -> Self {
+        self.script_detection = enabled;
+        self
+    }
+
+    /// Categories describing the Unicode scripts of a string's letters
+    ///
+    /// Emits `script:<name>` when a non-Latin script holds the majority of the letters,
+    /// and `mixed_script` when letters from more than one script are present.
+    fn script_categories(value: &str) -> Vec<StringCategory> {
+        let mut counts: Vec<(&'static str, usize)> = Vec::new();
+        let mut total = 0;
+        for script in value.chars().filter(|c| c.is_alphabetic()).map(char_script) {
+            total += 1;
+            match counts.iter_mut().find(|(name, _)| *name == script) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((script, 1)),
+            }
+        }
+
+        let mut categories = Vec::new();
+        let Some(&(majority, count)) = counts.iter().max_by_key(|(_, count)| *count) else {
+            return categories;
+        };
+
+        if majority != "latin" && count * 2 > total {
+            categories.push(StringCategory {
+                name: format!("script:{}", majority),
+                parent: Some("script".to_string()),
+                description: format!("Predominantly {} script", majority),
+            });
+        }
+
+        if counts.len() > 1 {
+            categories.push(StringCategory {
+                name: "mixed_script".to_string(),
+                parent: Some("script".to_string()),
+                description: "Letters from multiple scripts (possible homoglyphs)".to_string(),
+            });
+        }
+
+        categories
     }
 
     fn add_default_rules(&mut self) {
@@ -108,6 +180,30 @@ impl DefaultCategorizer {
             priority: 90,
         });
 
+        // UNC path categorization (\\host\share)
+        self.rules.push(CategoryRule {
+            name: "unc_rule".to_string(),
+            matcher: Box::new(|s| UNC_REGEX.is_match(s)),
+            category: StringCategory {
+                name: "unc_path".to_string(),
+                parent: Some("filesystem".to_string()),
+                description: "UNC network share path".to_string(),
+            },
+            priority: 90,
+        });
+
+        // Administrative share categorization (C$, ADMIN$, IPC$)
+        self.rules.push(CategoryRule {
+            name: "admin_share_rule".to_string(),
+            matcher: Box::new(|s| ADMIN_SHARE_REGEX.is_match(s)),
+            category: StringCategory {
+                name: "admin_share".to_string(),
+                parent: Some("lateral_movement".to_string()),
+                description: "Windows administrative share access".to_string(),
+            },
+            priority: 95,
+        });
+
         // Registry key categorization
         self.rules.push(CategoryRule {
             name: "registry_rule".to_string(),
@@ -165,6 +261,42 @@ impl DefaultCategorizer {
             priority: 95,
         });
 
+        // File hash categorization (MD5, SHA1, SHA256 hex digests)
+        self.rules.push(CategoryRule {
+            name: "hash_rule".to_string(),
+            matcher: Box::new(|s| HASH_REGEX.is_match(s)),
+            category: StringCategory {
+                name: "hash".to_string(),
+                parent: Some("crypto".to_string()),
+                description: "MD5, SHA1 or SHA256 hex digest".to_string(),
+            },
+            priority: 95,
+        });
+
+        // Cryptocurrency wallet categorization (Bitcoin and Ethereum)
+        self.rules.push(CategoryRule {
+            name: "crypto_wallet_rule".to_string(),
+            matcher: Box::new(is_crypto_wallet),
+            category: StringCategory {
+                name: "crypto_wallet".to_string(),
+                parent: Some("crypto".to_string()),
+                description: "Bitcoin or Ethereum wallet address".to_string(),
+            },
+            priority: 95,
+        });
+
+        // UUID categorization
+        self.rules.push(CategoryRule {
+            name: "uuid_rule".to_string(),
+            matcher: Box::new(|s| UUID_REGEX.is_match(s)),
+            category: StringCategory {
+                name: "uuid".to_string(),
+                parent: Some("identifier".to_string()),
+                description: "UUID / GUID".to_string(),
+            },
+            priority: 90,
+        });
+
         // Email categorization
         self.rules.push(CategoryRule {
             name: "email_rule".to_string(),
@@ -199,7 +331,7 @@ impl DefaultCategorizer {
         });
 
         // Sort rules by priority (descending)
-        self.rules.sort_by(|a, b| b.priority.cmp(&a.priority));
+        self.rules.sort_by_key(|r| std::cmp::Reverse(r.priority));
     }
 }
 
@@ -213,6 +345,20 @@ impl Categorizer for DefaultCategorizer {
             }
         }
 
+        if categories.iter().any(|c| c.name == "url") {
+            if let Some(domain) = extract_domain(value) {
+                categories.push(StringCategory {
+                    name: format!("domain:{}", domain),
+                    parent: Some("network".to_string()),
+                    description: "Registrable domain of a URL".to_string(),
+                });
+            }
+        }
+
+        if self.script_detection {
+            categories.extend(Self::script_categories(value));
+        }
+
         // If no specific category matched, return generic
         if categories.is_empty() {
             categories.push(StringCategory {
@@ -227,7 +373,7 @@ impl Categorizer for DefaultCategorizer {
 
     fn add_rule(&mut self, rule: CategoryRule) -> AnalysisResult<()> {
         self.rules.push(rule);
-        self.rules.sort_by(|a, b| b.priority.cmp(&a.priority));
+        self.rules.sort_by_key(|r| std::cmp::Reverse(r.priority));
         Ok(())
     }
 
@@ -250,8 +396,136 @@ impl Categorizer for DefaultCategorizer {
     }
 }
 
+/// Extract the registrable domain from a URL, e.g. `evil.com` from `https://sub.evil.com/a`
+///
+/// Uses the last two host labels, or three for common country-code second-level
+/// suffixes such as `co.uk`. IP hosts are returned as-is. Returns `None` when no host
+/// can be found.
+pub fn extract_domain(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+
+    let host = if let Some(bracketed) = host_port.strip_prefix('[') {
+        bracketed.split_once(']')?.0
+    } else {
+        host_port.split(':').next()?
+    };
+    let host = host.trim_end_matches('.').to_lowercase();
+
+    if host.is_empty() {
+        return None;
+    }
+    if host.contains(':') || IPV4_REGEX.is_match(&host) {
+        return Some(host);
+    }
+
+    let labels: Vec<&str> = host.split('.').collect();
+    if labels.iter().any(|l| l.is_empty()) {
+        return None;
+    }
+
+    let keep = match labels.as_slice() {
+        [.., second, tld]
+            if labels.len() >= 3
+                && tld.len() == 2
+                && matches!(*second, "co" | "com" | "net" | "org" | "gov" | "ac" | "edu") =>
+        {
+            3
+        }
+        _ => 2,
+    };
+
+    Some(labels[labels.len().saturating_sub(keep)..].join("."))
+}
+
+/// Whether `value` is a Bitcoin or Ethereum wallet address
+///
+/// Legacy Bitcoin addresses must mix letters and digits after the version character, so
+/// runs of digits such as numeric IDs are not mistaken for addresses.
+fn is_crypto_wallet(value: &str) -> bool {
+    if !CRYPTO_WALLET_REGEX.is_match(value) {
+        return false;
+    }
+    match value.strip_prefix(['1', '3']) {
+        Some(body) => {
+            body.bytes().any(|b| b.is_ascii_alphabetic())
+                && body.bytes().any(|b| b.is_ascii_digit())
+        }
+        None => true,
+    }
+}
+
+/// Unicode script of an alphabetic character, by block
+fn char_script(c: char) -> &'static str {
+    match c as u32 {
+        0x0000..=0x024F | 0x1E00..=0x1EFF => "latin",
+        0x0370..=0x03FF | 0x1F00..=0x1FFF => "greek",
+        0x0400..=0x052F => "cyrillic",
+        0x0590..=0x05FF => "hebrew",
+        0x0600..=0x06FF | 0x0750..=0x077F => "arabic",
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF => "cjk",
+        _ => "other",
+    }
+}
+
 impl Default for DefaultCategorizer {
     fn default() -> Self {
         Self::new()
     }
 }
+
+/// Categorizer that runs several categorizers and unions their results
+///
+/// Categories are reported in chain order; when several categorizers report the same
+/// category name, the first one's category is kept.
+pub struct ChainCategorizer {
+    categorizers: Vec<Box<dyn Categorizer>>,
+}
+
+impl ChainCategorizer {
+    /// Create a chain from the given categorizers
+    pub fn new(categorizers: Vec<Box<dyn Categorizer>>) -> Self {
+        Self { categorizers }
+    }
+
+    /// Append a categorizer to the chain
+    pub fn with_categorizer(mut self, categorizer: Box<dyn Categorizer>) -> Self {
+        self.categorizers.push(categorizer);
+        self
+    }
+
+    /// Keep the first category reported under each name
+    fn dedup(categories: impl Iterator<Item = StringCategory>) -> Vec<StringCategory> {
+        let mut seen = std::collections::HashSet::new();
+        categories.filter(|c| seen.insert(c.name.clone())).collect()
+    }
+}
+
+impl Categorizer for ChainCategorizer {
+    fn categorize(&self, value: &str) -> Vec<StringCategory> {
+        Self::dedup(self.categorizers.iter().flat_map(|c| c.categorize(value)))
+    }
+
+    /// Add the rule to the first categorizer in the chain
+    fn add_rule(&mut self, rule: CategoryRule) -> AnalysisResult<()> {
+        match self.categorizers.first_mut() {
+            Some(first) => first.add_rule(rule),
+            None => anyhow::bail!("cannot add rule '{}' to an empty chain", rule.name),
+        }
+    }
+
+    /// Remove the rule from every categorizer in the chain
+    fn remove_rule(&mut self, name: &str) -> AnalysisResult<()> {
+        for categorizer in &mut self.categorizers {
+            categorizer.remove_rule(name)?;
+        }
+        Ok(())
+    }
+
+    fn get_categories(&self) -> Vec<StringCategory> {
+        Self::dedup(self.categorizers.iter().flat_map(|c| c.get_categories()))
+    }
+}
@@ -0,0 +1,70 @@
+//! Error type returned by the string tracker
+
+use std::fmt;
+
+/// Errors returned by [`StringTracker`](crate::StringTracker) operations
+#[derive(Debug)]
+pub enum StringAnalysisError {
+    /// A thread panicked while holding the tracker's lock
+    LockPoisoned,
+    /// A regular expression in a filter failed to compile
+    InvalidRegex {
+        /// The pattern that failed to compile
+        pattern: String,
+        /// The underlying compile error
+        source: regex::Error,
+    },
+    /// Serializing or deserializing data failed
+    Serialization(Box<dyn std::error::Error + Send + Sync>),
+    /// Reading input or writing output failed
+    Io(std::io::Error),
+}
+
+impl fmt::Display for StringAnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LockPoisoned => write!(f, "string tracker lock poisoned"),
+            Self::InvalidRegex { pattern, source } => {
+                write!(f, "invalid regex_pattern '{}': {}", pattern, source)
+            }
+            Self::Serialization(e) => write!(f, "serialization error: {}", e),
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StringAnalysisError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::LockPoisoned => None,
+            Self::InvalidRegex { source, .. } => Some(source),
+            Self::Serialization(e) => Some(e.as_ref()),
+            Self::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for StringAnalysisError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Serialization(Box::new(e))
+    }
+}
+
+#[cfg(feature = "binary")]
+impl From<bincode::Error> for StringAnalysisError {
+    fn from(e: bincode::Error) -> Self {
+        Self::Serialization(e)
+    }
+}
+
+impl From<std::io::Error> for StringAnalysisError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for StringAnalysisError {
+    fn from(_: std::sync::PoisonError<T>) -> Self {
+        Self::LockPoisoned
+    }
+}
@@ -11,6 +11,7 @@
 //! - **Suspicious Pattern Detection**: Built-in patterns for malware and threat indicators
 //! - **Statistical Analysis**: Generate insights about string distributions and relationships
 //! - **Extensible Architecture**: Add custom patterns and categorization rules
+//! - **Async Support**: `AsyncStringTracker` for tokio runtimes (`tokio` feature)
 //!
 //! ## Quick Start
 //!
@@ -37,17 +38,31 @@
 //! ```
 
 mod analyzer;
+#[cfg(feature = "tokio")]
+mod async_tracker;
 mod categorizer;
+mod error;
 mod patterns;
 mod tracker;
 mod types;
 
 // Re-export main types
-pub use analyzer::{DefaultStringAnalyzer, StringAnalysis, StringAnalyzer, SuspiciousIndicator};
-pub use categorizer::{Categorizer, CategoryRule, DefaultCategorizer, StringCategory};
+pub use analyzer::{
+    DefaultStringAnalyzer, EntropyEstimator, ShannonEntropy, StringAnalysis, StringAnalyzer,
+    SuspiciousIndicator,
+};
+#[cfg(feature = "tokio")]
+pub use async_tracker::AsyncStringTracker;
+pub use categorizer::{
+    extract_domain, Categorizer, CategoryRule, ChainCategorizer, DefaultCategorizer, StringCategory,
+};
+pub use error::StringAnalysisError;
 pub use patterns::{DefaultPatternProvider, Pattern, PatternDef, PatternProvider};
 pub use tracker::{
-    StringContext, StringEntry, StringFilter, StringOccurrence, StringStatistics, StringTracker,
+    ClusterOptions, FilterExpr, Finding, IngestFailure, IngestReport, SearchOptions,
+    SimilarityWeights, StringAnalysisResult, StringContext, StringEntry, StringFilter,
+    StringOccurrence, StringStatistics, StringTracker, ThreatScoreWeights, TrackerDiff,
+    TrackerState,
 };
 pub use types::*;
 
@@ -1,8 +1,11 @@
 //! Pattern matching and pattern provider functionality
 
 use crate::types::AnalysisResult;
+use anyhow::{bail, Context};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{Read, Write};
 
 /// Represents a pattern used for string matching and categorization
 #[derive(Debug, Clone)]
@@ -10,6 +13,9 @@ pub struct Pattern {
     /// Unique name for the pattern
     pub name: String,
     /// Regular expression
+    ///
+    /// If the expression defines a named capture group, the first named group is
+    /// reported as the matched text instead of the whole match.
     pub regex: Regex,
     /// Category this pattern belongs to
     pub category: String,
@@ -21,6 +27,20 @@ pub struct Pattern {
     pub severity: u8,
 }
 
+impl Pattern {
+    /// Convert back to a serializable definition
+    pub fn to_def(&self) -> PatternDef {
+        PatternDef {
+            name: self.name.clone(),
+            regex: self.regex.as_str().to_string(),
+            category: self.category.clone(),
+            description: self.description.clone(),
+            is_suspicious: self.is_suspicious,
+            severity: self.severity,
+        }
+    }
+}
+
 /// Serializable pattern definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatternDef {
@@ -146,6 +166,16 @@ impl DefaultPatternProvider {
             severity: 5,
         })?;
 
+        // Lateral movement
+        provider.add_pattern(PatternDef {
+            name: "admin_share".to_string(),
+            regex: r"(?i)^\\\\[^\\/]+\\(?P<share>[A-Z]\$|ADMIN\$|IPC\$)(\\|$)".to_string(),
+            category: "admin_share".to_string(),
+            description: "Administrative share access (lateral movement)".to_string(),
+            is_suspicious: true,
+            severity: 7,
+        })?;
+
         // Credentials
         provider.add_pattern(PatternDef {
             name: "credential_keyword".to_string(),
@@ -194,6 +224,40 @@ impl DefaultPatternProvider {
             patterns: Vec::new(),
         }
     }
+
+    /// Load a JSON pattern pack (an array of `PatternDef`) and return the number added
+    ///
+    /// The whole pack is validated before anything is added: an invalid regex or a name
+    /// that is duplicated (within the pack or with an existing pattern) fails the load and
+    /// names the offending pattern.
+    pub fn load_patterns<R: Read>(&mut self, reader: R) -> AnalysisResult<usize> {
+        let defs: Vec<PatternDef> =
+            serde_json::from_reader(reader).context("failed to parse pattern pack")?;
+
+        let mut names: HashSet<&str> = self.patterns.iter().map(|p| p.name.as_str()).collect();
+        let mut compiled = Vec::with_capacity(defs.len());
+        for def in &defs {
+            if !names.insert(def.name.as_str()) {
+                bail!("duplicate pattern name '{}'", def.name);
+            }
+            let pattern = def
+                .clone()
+                .compile()
+                .with_context(|| format!("invalid regex in pattern '{}'", def.name))?;
+            compiled.push(pattern);
+        }
+
+        let added = compiled.len();
+        self.patterns.extend(compiled);
+        Ok(added)
+    }
+
+    /// Export all patterns as a JSON pattern pack loadable by `load_patterns`
+    pub fn export_patterns<W: Write>(&self, writer: W) -> AnalysisResult<()> {
+        let defs: Vec<PatternDef> = self.patterns.iter().map(Pattern::to_def).collect();
+        serde_json::to_writer_pretty(writer, &defs)?;
+        Ok(())
+    }
 }
 
 impl PatternProvider for DefaultPatternProvider {
@@ -1,24 +1,50 @@
 //! String tracking and analysis functionality
 
-use crate::analyzer::{DefaultStringAnalyzer, StringAnalyzer};
-use crate::categorizer::{Categorizer, DefaultCategorizer};
+use crate::analyzer::{DefaultStringAnalyzer, StringAnalyzer, SuspiciousIndicator};
+use crate::categorizer::{Categorizer, DefaultCategorizer, StringCategory};
+use crate::error::StringAnalysisError;
 use crate::patterns::{DefaultPatternProvider, PatternProvider};
-use anyhow::Result;
-use chrono::{DateTime, Utc};
+use crate::types::AnalysisConfig;
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
+use std::borrow::Cow;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet};
+#[cfg(feature = "binary")]
+use std::io::Read;
+use std::io::{BufRead, Write};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 // Type aliases to reduce complexity
+type Result<T> = std::result::Result<T, StringAnalysisError>;
 type StringCountVec = Vec<(String, usize)>;
 type StringScoreVec = Vec<(String, f64)>;
 type DateTimeRange = (DateTime<Utc>, DateTime<Utc>);
-type StringEntryMap = Arc<Mutex<HashMap<String, StringEntry>>>;
+type StringEntryMap = Arc<Mutex<EntryStore>>;
 type BoxedAnalyzer = Arc<Box<dyn StringAnalyzer>>;
 type BoxedCategorizer = Arc<Box<dyn Categorizer>>;
+type SuspiciousCallback = Arc<dyn Fn(&StringEntry) + Send + Sync>;
+type SuspiciousCallbacks = Arc<Mutex<Vec<SuspiciousCallback>>>;
+type KeyNormalizer = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+// PowerShell invocation markers: the binary itself or an IEX call. Launch flags such as
+// -enc or -nop are not markers on their own, since they also occur in URLs and paths.
+static POWERSHELL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:powershell|pwsh)(?:\.exe)?\b|\biex[\s(]|\binvoke-expression\b").unwrap()
+});
+
+// A flag starting with "e" followed by a base64 argument; the flag is checked separately
+// since PowerShell accepts any prefix of -EncodedCommand
+static ENCODED_COMMAND_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)(?:^|\s)[-/](e[a-z]*)\s+['"]?([A-Za-z0-9+/]{4,}={0,2})"#).unwrap()
+});
 
 /// Context in which a string was found
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum StringContext {
     /// String found in file content
     FileString {
@@ -102,21 +128,80 @@ pub struct StringEntry {
     /// Timestamp when this string was last seen
     pub last_seen: DateTime<Utc>,
     /// Total number of times this string has been found
+    ///
+    /// Keeps counting past the tracker's occurrence cap, so it may exceed `occurrences.len()`.
     pub total_occurrences: usize,
     /// Set of unique file paths where this string was found
     pub unique_files: HashSet<String>,
-    /// Detailed records of each occurrence
+    /// Detailed records of each retained occurrence, in insertion order rather than time
+    /// order; see [`occurrences_sorted`](Self::occurrences_sorted)
     pub occurrences: Vec<StringOccurrence>,
     /// Set of categories this string belongs to
     pub categories: HashSet<String>,
     /// Whether this string is flagged as suspicious
     pub is_suspicious: bool,
-    /// Shannon entropy score of the string
+    /// Shannon entropy over the string's characters (see `byte_entropy` for bytes)
     pub entropy: f64,
+    /// Sum of the severities of the suspicious indicators matched by the string, raised to
+    /// any category severity override
+    #[serde(default)]
+    pub suspicion_score: u32,
+    /// Shannon entropy over the string's UTF-8 bytes
+    #[serde(default)]
+    pub byte_entropy: f64,
+    /// Indicators matched when the string was first analyzed, explaining `is_suspicious`
+    #[serde(default)]
+    pub suspicious_indicators: Vec<SuspiciousIndicator>,
+    /// Set of distinct tools that reported this string, kept past occurrence eviction
+    #[serde(default)]
+    pub unique_tools: HashSet<String>,
+    /// Fraction of characters that are printable (0.0 for binary data, 1.0 for clean text)
+    #[serde(default = "default_printable_ratio")]
+    pub printable_ratio: f64,
 }
 
-/// Statistics about tracked strings
+impl StringEntry {
+    /// Length of the string in characters, as used by length filters and statistics
+    pub fn char_len(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    /// Length of the string in UTF-8 bytes
+    pub fn byte_len(&self) -> usize {
+        self.value.len()
+    }
+
+    /// Retained occurrences ordered by timestamp, oldest first
+    ///
+    /// Occurrences sharing a timestamp keep their insertion order.
+    pub fn occurrences_sorted(&self) -> Vec<&StringOccurrence> {
+        let mut sorted: Vec<_> = self.occurrences.iter().collect();
+        sorted.sort_by_key(|o| o.timestamp);
+        sorted
+    }
+}
+
+/// Analysis of a single string computed without tracking it
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StringAnalysisResult {
+    /// The analyzed string value
+    pub value: String,
+    /// Shannon entropy over the string's characters (see `byte_entropy` for bytes)
+    pub entropy: f64,
+    /// Shannon entropy over the string's UTF-8 bytes
+    pub byte_entropy: f64,
+    /// Categories from the categorizer and the analyzer's patterns
+    pub categories: HashSet<String>,
+    /// Whether the string is flagged as suspicious
+    pub is_suspicious: bool,
+    /// Sum of the severities of the suspicious indicators
+    pub suspicion_score: u32,
+    /// Suspicious indicators found by the analyzer
+    pub suspicious_indicators: Vec<SuspiciousIndicator>,
+}
+
+/// Statistics about tracked strings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StringStatistics {
     /// Total number of unique strings tracked
     pub total_unique_strings: usize,
@@ -132,8 +217,11 @@ pub struct StringStatistics {
     pub high_entropy_strings: StringScoreVec,
     /// Distribution of strings across different categories
     pub category_distribution: HashMap<String, usize>,
-    /// Distribution of strings by length ranges
+    /// Distribution of strings by length ranges (in characters)
     pub length_distribution: HashMap<String, usize>,
+    /// Highest suspicion score among the strings in each category
+    #[serde(default)]
+    pub category_severity: HashMap<String, u32>,
 }
 
 /// Filter criteria for string queries
@@ -143,11 +231,11 @@ pub struct StringFilter {
     pub min_occurrences: Option<usize>,
     /// Maximum number of occurrences a string can have
     pub max_occurrences: Option<usize>,
-    /// Minimum length of strings to include
+    /// Minimum length of strings to include, in characters
     pub min_length: Option<usize>,
-    /// Maximum length of strings to include
+    /// Maximum length of strings to include, in characters
     pub max_length: Option<usize>,
-    /// Filter by specific categories
+    /// Filter by specific categories; `*` matches any run of characters (e.g. `script:*`)
     pub categories: Option<Vec<String>>,
     /// Filter by specific file paths
     pub file_paths: Option<Vec<String>>,
@@ -163,15 +251,345 @@ pub struct StringFilter {
     pub max_entropy: Option<f64>,
     /// Date range filter for when strings were discovered
     pub date_range: Option<DateTimeRange>,
+    /// Minimum suspicion score for strings
+    pub min_suspicion_score: Option<u32>,
+    /// Maximum suspicion score for strings
+    pub max_suspicion_score: Option<u32>,
+    /// Filter by tools that reported the string
+    pub tools: Option<Vec<String>>,
+    /// Minimum fraction of printable characters
+    pub min_printable_ratio: Option<f64>,
+    /// Maximum fraction of printable characters
+    pub max_printable_ratio: Option<f64>,
+}
+
+impl StringFilter {
+    /// Check that the filter is usable, e.g. that `regex_pattern` compiles
+    pub fn validate(&self) -> Result<()> {
+        CompiledFilter::new(self).map(|_| ())
+    }
+}
+
+/// Boolean combination of [`StringFilter`]s
+///
+/// Each `Leaf` keeps the flat AND semantics of `StringFilter`. An empty `And` matches every
+/// string and an empty `Or` matches none.
+// Leaves are by far the most common node, so they are stored inline rather than boxed
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FilterExpr {
+    /// Strings matching the filter
+    Leaf(StringFilter),
+    /// Strings matching every sub-expression
+    And(Vec<FilterExpr>),
+    /// Strings matching at least one sub-expression
+    Or(Vec<FilterExpr>),
+    /// Strings not matching the sub-expression
+    Not(Box<FilterExpr>),
+}
+
+impl FilterExpr {
+    /// Check that every filter in the expression is usable
+    pub fn validate(&self) -> Result<()> {
+        CompiledExpr::new(self).map(|_| ())
+    }
+}
+
+impl From<StringFilter> for FilterExpr {
+    fn from(filter: StringFilter) -> Self {
+        Self::Leaf(filter)
+    }
+}
+
+/// A `FilterExpr` with every leaf compiled once per scan
+pub(crate) enum CompiledExpr<'a> {
+    Leaf(CompiledFilter<'a>),
+    And(Vec<CompiledExpr<'a>>),
+    Or(Vec<CompiledExpr<'a>>),
+    Not(Box<CompiledExpr<'a>>),
+}
+
+impl<'a> CompiledExpr<'a> {
+    fn new(expr: &'a FilterExpr) -> Result<Self> {
+        Ok(match expr {
+            FilterExpr::Leaf(filter) => Self::Leaf(CompiledFilter::new(filter)?),
+            FilterExpr::And(exprs) => {
+                Self::And(exprs.iter().map(Self::new).collect::<Result<_>>()?)
+            }
+            FilterExpr::Or(exprs) => Self::Or(exprs.iter().map(Self::new).collect::<Result<_>>()?),
+            FilterExpr::Not(expr) => Self::Not(Box::new(Self::new(expr)?)),
+        })
+    }
+
+    /// Compile an optional flat filter as a single leaf
+    pub(crate) fn compile(filter: Option<&'a StringFilter>) -> Result<Option<Self>> {
+        filter
+            .map(|f| CompiledFilter::new(f).map(Self::Leaf))
+            .transpose()
+    }
+}
+
+/// A `StringFilter` with its regex compiled once per scan
+pub(crate) struct CompiledFilter<'a> {
+    filter: &'a StringFilter,
+    regex: Option<Regex>,
+    categories: Option<Vec<CategoryPattern<'a>>>,
+}
+
+/// A `categories` filter entry, split on `*` wildcards once per scan
+enum CategoryPattern<'a> {
+    Exact(&'a str),
+    Glob(Vec<&'a str>),
+}
+
+impl<'a> CategoryPattern<'a> {
+    fn new(pattern: &'a str) -> Self {
+        if pattern.contains('*') {
+            Self::Glob(pattern.split('*').collect())
+        } else {
+            Self::Exact(pattern)
+        }
+    }
+
+    fn matches(&self, categories: &HashSet<String>) -> bool {
+        match self {
+            Self::Exact(name) => categories.contains(*name),
+            Self::Glob(parts) => categories.iter().any(|c| glob_match(parts, c)),
+        }
+    }
+}
+
+impl<'a> CompiledFilter<'a> {
+    fn new(filter: &'a StringFilter) -> Result<Self> {
+        let regex = filter
+            .regex_pattern
+            .as_deref()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|source| StringAnalysisError::InvalidRegex {
+                    pattern: pattern.to_string(),
+                    source,
+                })
+            })
+            .transpose()?;
+        let categories = filter
+            .categories
+            .as_ref()
+            .map(|patterns| patterns.iter().map(|p| CategoryPattern::new(p)).collect());
+
+        Ok(Self {
+            filter,
+            regex,
+            categories,
+        })
+    }
+}
+
+/// A suspicious string packaged for export to case-management systems
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    /// Stable identifier derived from the string and the hashes of the files it was seen in
+    pub id: String,
+    /// The suspicious string value
+    pub value: String,
+    /// Names of the patterns (techniques) the string matched, sorted
+    pub techniques: Vec<String>,
+    /// Highest severity among the matched indicators (0-10)
+    pub severity: u8,
+    /// Retained occurrences backing the finding
+    pub evidence: Vec<StringOccurrence>,
+}
+
+/// A single line of JSON Lines input accepted by [`StringTracker::ingest_jsonl`]
+#[derive(Debug, Deserialize)]
+struct IngestRecord {
+    value: String,
+    file_path: String,
+    file_hash: String,
+    tool_name: String,
+    context: StringContext,
+}
+
+/// A line that could not be ingested
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestFailure {
+    /// 1-based line number in the input
+    pub line: usize,
+    /// Why the line was rejected
+    pub error: String,
+}
+
+/// Outcome of [`StringTracker::ingest_jsonl`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IngestReport {
+    /// Number of records tracked
+    pub succeeded: usize,
+    /// Number of malformed lines skipped
+    pub failed: usize,
+    /// Details of each skipped line
+    pub failures: Vec<IngestFailure>,
+}
+
+/// Serializable snapshot of a tracker's entries
+///
+/// Configuration (analyzer, categorizer, caps, callbacks) is not part of the state; it
+/// belongs to the tracker the state is loaded into.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackerState {
+    /// Format version of the snapshot
+    pub version: u32,
+    /// Tracked entries, ordered by value
+    pub entries: Vec<StringEntry>,
+}
+
+impl TrackerState {
+    /// Current snapshot format version
+    pub const VERSION: u32 = 1;
+}
+
+/// Differences between two tracker states, from [`StringTracker::diff`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrackerDiff {
+    /// Strings tracked only in the other tracker, sorted
+    pub added: Vec<String>,
+    /// Strings tracked only in this tracker, sorted
+    pub removed: Vec<String>,
+    /// Strings tracked in both whose `total_occurrences` differ, with the change
+    /// (other minus this), sorted by value
+    pub changed: Vec<(String, isize)>,
+}
+
+/// Options for [`StringTracker::cluster_strings`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterOptions {
+    /// Length of the character n-grams compared between strings
+    pub ngram_size: usize,
+    /// Minimum Jaccard similarity of two strings' n-gram sets to link them
+    pub similarity_threshold: f64,
+    /// Clusters with fewer members are dropped
+    pub min_cluster_size: usize,
+}
+
+impl Default for ClusterOptions {
+    fn default() -> Self {
+        Self {
+            ngram_size: 3,
+            similarity_threshold: 0.5,
+            min_cluster_size: 2,
+        }
+    }
+}
+
+/// Options for [`StringTracker::search_strings_fuzzy`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchOptions {
+    /// Maximum edit distance for a fuzzy (typo-tolerant) match; 0 disables fuzzy matching
+    pub max_edit_distance: usize,
+    /// Match case-sensitively
+    pub case_sensitive: bool,
+    /// Share of the score (0.0-1.0) contributed by occurrence count rather than match type
+    pub occurrence_weight: f64,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            max_edit_distance: 2,
+            case_sensitive: false,
+            occurrence_weight: 0.1,
+        }
+    }
+}
+
+/// Weights and cutoffs for [`StringTracker::get_related_strings_weighted`]
+///
+/// Similarity is the weighted mean of the factors that apply to a pair: shared files and
+/// shared categories count only when there is some overlap, entropy only when the
+/// entropies are within `entropy_window`, and length always. A factor with zero weight is
+/// ignored entirely.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarityWeights {
+    /// Weight of the shared file ratio
+    pub files: f64,
+    /// Weight of the shared category ratio
+    pub categories: f64,
+    /// Weight of entropy closeness
+    pub entropy: f64,
+    /// Weight of the length ratio
+    pub length: f64,
+    /// Largest entropy difference still considered similar
+    pub entropy_window: f64,
+    /// Minimum similarity for a string to be reported as related (exclusive)
+    pub cutoff: f64,
+}
+
+impl Default for SimilarityWeights {
+    fn default() -> Self {
+        Self {
+            files: 1.0,
+            categories: 1.0,
+            entropy: 1.0,
+            length: 1.0,
+            entropy_window: 0.5,
+            cutoff: 0.3,
+        }
+    }
+}
+
+/// Weights for the per-file threat score
+///
+/// The score is `100 * Σ(weight_i * signal_i) / Σ(weight_i)`, where every signal is
+/// normalized to `0.0..=1.0` and computed over the suspicious strings seen in the file:
+///
+/// - `suspicious_count`: `n / (n + saturation)` for `n` suspicious strings
+/// - `category_diversity`: `d / (d + saturation)` for `d` distinct categories
+/// - `max_severity`: highest indicator severity divided by 10
+/// - `rarity`: mean of `1 / unique_files` per string, so strings unique to this file count most
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreatScoreWeights {
+    /// Weight of the number of suspicious strings
+    pub suspicious_count: f64,
+    /// Weight of the number of distinct categories among suspicious strings
+    pub category_diversity: f64,
+    /// Weight of the highest indicator severity
+    pub max_severity: f64,
+    /// Weight of how rarely the suspicious strings appear in other files
+    pub rarity: f64,
+    /// Count at which the count-based signals reach half of their maximum
+    pub saturation: f64,
+}
+
+impl Default for ThreatScoreWeights {
+    fn default() -> Self {
+        Self {
+            suspicious_count: 0.35,
+            category_diversity: 0.15,
+            max_severity: 0.3,
+            rarity: 0.2,
+            saturation: 5.0,
+        }
+    }
 }
 
 /// Main string tracking system
+///
+/// If a thread panics while holding the tracker's lock, methods returning `Result`
+/// fail with [`StringAnalysisError::LockPoisoned`]; infallible queries keep reading the
+/// entries as they were left.
 #[derive(Clone)]
 pub struct StringTracker {
     entries: StringEntryMap,
     analyzer: BoxedAnalyzer,
     categorizer: BoxedCategorizer,
     max_occurrences_per_string: usize,
+    enable_time_analysis: bool,
+    deduplicate_occurrences: bool,
+    suspicious_callbacks: SuspiciousCallbacks,
+    ignore_list: HashSet<String>,
+    ignore_patterns: Vec<Regex>,
+    category_severity_overrides: HashMap<String, u32>,
+    max_entries: Option<usize>,
+    evicted_count: Arc<AtomicUsize>,
+    key_normalizer: Option<KeyNormalizer>,
 }
 
 impl Default for StringTracker {
@@ -183,36 +601,224 @@ impl Default for StringTracker {
 impl StringTracker {
     /// Create a new StringTracker with default analyzer and categorizer
     pub fn new() -> Self {
+        Self::from_config(AnalysisConfig::default())
+    }
+
+    /// Create a StringTracker with the default components configured from `config`
+    ///
+    /// `min_suspicious_entropy` sets the analyzer's entropy threshold and
+    /// `max_occurrences_per_string` bounds the stored occurrences. With
+    /// `enable_time_analysis` disabled, `last_seen` is no longer advanced after a
+    /// string is first tracked.
+    pub fn from_config(config: AnalysisConfig) -> Self {
         let pattern_provider = DefaultPatternProvider::default();
-        let analyzer = DefaultStringAnalyzer::new().with_patterns(pattern_provider.get_patterns());
+        let analyzer = DefaultStringAnalyzer::new()
+            .with_patterns(pattern_provider.get_patterns())
+            .with_entropy_threshold(config.min_suspicious_entropy);
 
-        Self {
-            entries: Arc::new(Mutex::new(HashMap::new())),
-            analyzer: Arc::new(Box::new(analyzer)),
-            categorizer: Arc::new(Box::new(DefaultCategorizer::new())),
-            max_occurrences_per_string: 1000,
-        }
+        Self::from_parts(
+            Box::new(analyzer),
+            Box::new(DefaultCategorizer::new()),
+            &config,
+        )
     }
 
     /// Create a StringTracker with custom analyzer and categorizer
     pub fn with_components(
         analyzer: Box<dyn StringAnalyzer>,
         categorizer: Box<dyn Categorizer>,
+    ) -> Self {
+        Self::from_parts(analyzer, categorizer, &AnalysisConfig::default())
+    }
+
+    /// Single place where every tracker field is initialized
+    fn from_parts(
+        analyzer: Box<dyn StringAnalyzer>,
+        categorizer: Box<dyn Categorizer>,
+        config: &AnalysisConfig,
     ) -> Self {
         Self {
-            entries: Arc::new(Mutex::new(HashMap::new())),
+            entries: Arc::new(Mutex::new(EntryStore::default())),
             analyzer: Arc::new(analyzer),
             categorizer: Arc::new(categorizer),
-            max_occurrences_per_string: 1000,
+            max_occurrences_per_string: config.max_occurrences_per_string,
+            enable_time_analysis: config.enable_time_analysis,
+            deduplicate_occurrences: false,
+            suspicious_callbacks: Arc::new(Mutex::new(Vec::new())),
+            ignore_list: HashSet::new(),
+            ignore_patterns: Vec::new(),
+            category_severity_overrides: HashMap::new(),
+            max_entries: None,
+            evicted_count: Arc::new(AtomicUsize::new(0)),
+            key_normalizer: None,
         }
     }
 
+    /// Lock the entries, failing if a thread panicked while holding the lock
+    fn try_lock_entries(&self) -> Result<MutexGuard<'_, EntryStore>> {
+        Ok(self.entries.lock()?)
+    }
+
+    /// Lock the entries for an infallible operation, recovering them if the lock is poisoned
+    ///
+    /// Entries are only inserted once fully analyzed, so a panic in the analyzer or
+    /// categorizer cannot leave a half-built entry behind.
+    fn lock_entries(&self) -> MutexGuard<'_, EntryStore> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Move all tracked entries out of this tracker, leaving it empty
+    #[cfg(feature = "tokio")]
+    pub(crate) fn take_entries(&self) -> EntryStore {
+        std::mem::take(&mut *self.lock_entries())
+    }
+
     /// Set the maximum number of occurrences to track per string
+    ///
+    /// Entries already holding more occurrences are truncated immediately, keeping the
+    /// most recent ones. `total_occurrences` keeps counting past the cap; only the stored
+    /// occurrence records are limited.
     pub fn with_max_occurrences(mut self, max: usize) -> Self {
         self.max_occurrences_per_string = max;
+        let mut entries = self.lock_entries();
+        for entry in entries.values_mut() {
+            Self::truncate_occurrences(entry, max);
+        }
+        drop(entries);
+        self
+    }
+
+    /// Maximum number of occurrence records stored per string
+    pub fn max_occurrences_per_string(&self) -> usize {
+        self.max_occurrences_per_string
+    }
+
+    /// Drop the oldest stored occurrences of `entry` beyond `max`
+    fn truncate_occurrences(entry: &mut StringEntry, max: usize) {
+        let excess = entry.occurrences.len().saturating_sub(max);
+        entry.occurrences.drain(..excess);
+    }
+
+    /// Ignore repeated sightings of an identical occurrence
+    ///
+    /// When enabled, an occurrence with the same file hash and context (including offset)
+    /// as a retained occurrence of the string only refreshes `last_seen`; it is not stored
+    /// again and does not increase `total_occurrences`.
+    pub fn with_deduplicate_occurrences(mut self, deduplicate: bool) -> Self {
+        self.deduplicate_occurrences = deduplicate;
+        self
+    }
+
+    /// Never track the given exact strings
+    ///
+    /// Ignored strings are skipped before analysis, so they cost no analyzer work and
+    /// never appear in entries or statistics.
+    pub fn with_ignore_list(mut self, values: HashSet<String>) -> Self {
+        self.ignore_list = values;
+        self
+    }
+
+    /// Never track strings matching any of the given patterns
+    ///
+    /// Matching strings are skipped before analysis, like those in the ignore list.
+    pub fn with_ignore_patterns(mut self, patterns: Vec<Regex>) -> Self {
+        self.ignore_patterns = patterns;
+        self
+    }
+
+    /// Bound the number of distinct strings tracked
+    ///
+    /// When a new string would exceed the limit, the least recently seen string (smallest
+    /// `last_seen`, ties broken by key) is evicted first, in O(log n). With time analysis
+    /// disabled, `last_seen` is not advanced, so the oldest string is evicted instead.
+    /// The index behind eviction is only maintained for trackers with a cap.
+    pub fn with_max_entries(mut self, max: usize) -> Self {
+        self.max_entries = Some(max.max(1));
+        self.lock_entries().track_recency();
+        self
+    }
+
+    /// Number of strings evicted to respect the entry limit
+    pub fn evicted_count(&self) -> usize {
+        self.evicted_count.load(AtomicOrdering::Relaxed)
+    }
+
+    /// Evict least recently seen strings until there is room for one more
+    fn evict_for_insert(&self, entries: &mut EntryStore) {
+        let Some(max) = self.max_entries else {
+            return;
+        };
+
+        while entries.len() >= max && entries.pop_least_recent().is_some() {
+            self.evicted_count.fetch_add(1, AtomicOrdering::Relaxed);
+        }
+    }
+
+    /// Normalize string values before they are used as entry keys
+    ///
+    /// Values that normalize to the same key are tracked as a single entry whose `value` is
+    /// the first raw form seen, e.g. trimming and lowercasing merges `"Foo "` and `"foo"`.
+    /// Lookups such as [`get_string_details`](Self::get_string_details) normalize their
+    /// argument the same way. Set the normalizer before tracking any strings.
+    pub fn with_key_normalizer(
+        mut self,
+        normalizer: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.key_normalizer = Some(Arc::new(normalizer));
+        self
+    }
+
+    /// Map key under which `value` is tracked
+    pub(crate) fn entry_key<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        match &self.key_normalizer {
+            Some(normalize) => Cow::Owned(normalize(value)),
+            None => Cow::Borrowed(value),
+        }
+    }
+
+    /// Minimum category override severity that flags a string as suspicious
+    pub const SUSPICIOUS_OVERRIDE_SEVERITY: u32 = 5;
+
+    /// Maximum number of buckets returned by [`occurrence_timeline`](Self::occurrence_timeline)
+    pub const MAX_TIMELINE_BUCKETS: usize = 100_000;
+
+    /// Assign severities to categories, overriding the analyzer's verdict at track time
+    ///
+    /// A string in an overridden category gets a suspicion score of at least the override
+    /// severity, and is flagged as suspicious when the severity is at least
+    /// [`SUSPICIOUS_OVERRIDE_SEVERITY`](Self::SUSPICIOUS_OVERRIDE_SEVERITY). Overrides only
+    /// raise scores; they never clear a flag raised by the analyzer.
+    pub fn with_category_severity_overrides(mut self, overrides: HashMap<String, u32>) -> Self {
+        self.category_severity_overrides = overrides;
         self
     }
 
+    /// Apply category severity overrides to a suspicion score and flag
+    fn apply_severity_overrides(
+        &self,
+        categories: &HashSet<String>,
+        suspicion_score: u32,
+        is_suspicious: bool,
+    ) -> (u32, bool) {
+        let Some(severity) = categories
+            .iter()
+            .filter_map(|c| self.category_severity_overrides.get(c))
+            .max()
+        else {
+            return (suspicion_score, is_suspicious);
+        };
+
+        (
+            suspicion_score.max(*severity),
+            is_suspicious || *severity >= Self::SUSPICIOUS_OVERRIDE_SEVERITY,
+        )
+    }
+
+    /// Whether `value` is on the ignore list or matches an ignore pattern
+    pub(crate) fn is_ignored(&self, value: &str) -> bool {
+        self.ignore_list.contains(value) || self.ignore_patterns.iter().any(|p| p.is_match(value))
+    }
+
     /// Track a string occurrence
     pub fn track_string(
         &self,
@@ -222,18 +828,130 @@ impl StringTracker {
         tool_name: &str,
         context: StringContext,
     ) -> Result<()> {
-        let mut entries = self.entries.lock().unwrap();
+        self.track_string_at(value, file_path, file_hash, tool_name, context, Utc::now())
+    }
+
+    /// Track a string occurrence observed at `timestamp`
+    ///
+    /// The timestamp is used for the occurrence, for `first_seen` when the string is new,
+    /// and for `last_seen`.
+    pub fn track_string_at(
+        &self,
+        value: &str,
+        file_path: &str,
+        file_hash: &str,
+        tool_name: &str,
+        context: StringContext,
+        timestamp: DateTime<Utc>,
+    ) -> Result<()> {
+        if self.is_ignored(value) {
+            return Ok(());
+        }
 
         let occurrence = StringOccurrence {
             file_path: file_path.to_string(),
             file_hash: file_hash.to_string(),
             tool_name: tool_name.to_string(),
-            timestamp: Utc::now(),
-            context: context.clone(),
+            timestamp,
+            context,
         };
 
+        let new_suspicious = {
+            let mut entries = self.try_lock_entries()?;
+            self.record_occurrence(&mut entries, value, occurrence)
+        };
+        if let Some(entry) = new_suspicious {
+            self.notify_new_suspicious(&entry);
+        }
+        Ok(())
+    }
+
+    /// Track a string, then track each URL, IP address and path embedded in it
+    ///
+    /// The full string is tracked with `base_context`. It is then split into
+    /// whitespace- and quote-delimited tokens, which are run through the categorizer;
+    /// every token categorized as `url`, `ip_address` or `path` is tracked as its own
+    /// entry with a derived context and an `extracted_from:<hash>` category linking it
+    /// to the parent string, where `<hash>` is a stable hash of the parent value.
+    /// Path tokens also need a drive letter, a UNC prefix or at least two separators, so
+    /// command-line switches such as `/c` are not extracted.
+    pub fn track_string_with_extraction(
+        &self,
+        value: &str,
+        file_path: &str,
+        file_hash: &str,
+        tool_name: &str,
+        base_context: StringContext,
+    ) -> Result<()> {
+        self.track_string(value, file_path, file_hash, tool_name, base_context)?;
+
+        let link = format!("extracted_from:{:016x}", stable_hash([value]));
+        let tokens = value
+            .split(|c: char| c.is_whitespace() || c == '"' || c == '\'')
+            .map(|t| t.trim_matches(|c: char| matches!(c, ',' | ';' | '(' | ')')))
+            .filter(|t| !t.is_empty() && *t != value);
+
+        for token in tokens {
+            let categories = self.categorizer.categorize(token);
+            let context = if categories.iter().any(|c| c.name == "ip_address") {
+                StringContext::Other {
+                    category: "ip_address".to_string(),
+                }
+            } else if categories.iter().any(|c| c.name == "url")
+                || (categories.iter().any(|c| c.name == "path") && has_path_shape(token))
+            {
+                Self::default_context(token, &categories)
+            } else {
+                continue;
+            };
+
+            self.track_string(token, file_path, file_hash, tool_name, context)?;
+            let key = self.entry_key(token);
+            if let Some(entry) = self.try_lock_entries()?.get_mut(key.as_ref()) {
+                entry.categories.insert(link.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Register a callback invoked once for each newly tracked suspicious string
+    ///
+    /// The callback runs when a string is first inserted and flagged as suspicious,
+    /// after the entries lock has been released, so it may call back into the tracker.
+    /// Callbacks are shared between clones of the tracker and run in registration order.
+    pub fn on_new_suspicious(&self, callback: impl Fn(&StringEntry) + Send + Sync + 'static) {
+        self.suspicious_callbacks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Arc::new(callback));
+    }
+
+    /// Invoke the registered new-suspicious-string callbacks for `entry`
+    pub(crate) fn notify_new_suspicious(&self, entry: &StringEntry) {
+        let callbacks = self
+            .suspicious_callbacks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        for callback in callbacks {
+            callback(entry);
+        }
+    }
+
+    /// Record an occurrence of `value` in `entries`, analyzing the string if it is new
+    ///
+    /// Returns a snapshot of the entry if it was newly inserted as suspicious and
+    /// callbacks are registered, for delivery once the caller has released its lock.
+    pub(crate) fn record_occurrence(
+        &self,
+        entries: &mut EntryStore,
+        value: &str,
+        occurrence: StringOccurrence,
+    ) -> Option<StringEntry> {
+        let timestamp = occurrence.timestamp;
+
         // Get category from context
-        let context_category = match &context {
+        let context_category = match &occurrence.context {
             StringContext::FileString { .. } => "file_string",
             StringContext::Import { .. } => "import",
             StringContext::Export { .. } => "export",
@@ -247,46 +965,129 @@ impl StringTracker {
             StringContext::Other { category } => category,
         };
 
-        let entry = entries.entry(value.to_string()).or_insert_with(|| {
-            let analysis = self.analyzer.analyze(value);
-            let categories = self.categorizer.categorize(value);
+        let key = self.entry_key(value).into_owned();
+        let inserted = !entries.contains_key(&key);
+        if inserted {
+            self.evict_for_insert(entries);
+            let analysis = self.analyze_value(value);
 
-            let mut category_set =
-                HashSet::with_capacity(categories.len() + analysis.categories.len() + 1);
-            category_set.insert(context_category.to_string());
-            for cat in categories {
-                category_set.insert(cat.name);
-            }
-            category_set.extend(analysis.categories);
+            let mut categories = analysis.categories;
+            categories.insert(context_category.to_string());
+            let (suspicion_score, is_suspicious) = self.apply_severity_overrides(
+                &categories,
+                analysis.suspicion_score,
+                analysis.is_suspicious,
+            );
 
-            let now = Utc::now();
-            StringEntry {
-                value: value.to_string(),
-                first_seen: now,
-                last_seen: now,
+            let entry = StringEntry {
+                value: analysis.value,
+                first_seen: timestamp,
+                last_seen: timestamp,
                 total_occurrences: 0,
                 unique_files: HashSet::new(),
+                unique_tools: HashSet::new(),
                 occurrences: Vec::new(),
-                categories: category_set,
-                is_suspicious: analysis.is_suspicious,
+                categories,
+                is_suspicious,
                 entropy: analysis.entropy,
+                suspicion_score,
+                byte_entropy: analysis.byte_entropy,
+                printable_ratio: printable_ratio(value),
+                suspicious_indicators: analysis.suspicious_indicators,
+            };
+            if let Some(recency) = &mut entries.recency {
+                recency.insert((timestamp, key.clone()));
             }
-        });
+            entries.map.insert(key.clone(), entry);
+        }
+        let entry = entries.map.get_mut(&key)?;
+
+        // Keep first_seen <= every occurrence timestamp <= last_seen, even when
+        // occurrences arrive out of order
+        if self.enable_time_analysis {
+            entry.first_seen = entry.first_seen.min(timestamp);
+            if timestamp > entry.last_seen {
+                if let Some(recency) = &mut entries.recency {
+                    let stale = (entry.last_seen, key);
+                    recency.remove(&stale);
+                    recency.insert((timestamp, stale.1));
+                }
+                entry.last_seen = timestamp;
+            }
+        }
+
+        // Recorded before deduplication and eviction so tool attribution is never lost
+        if !entry.unique_tools.contains(&occurrence.tool_name) {
+            entry.unique_tools.insert(occurrence.tool_name.clone());
+        }
+
+        if self.deduplicate_occurrences
+            && entry
+                .occurrences
+                .iter()
+                .any(|o| o.file_hash == occurrence.file_hash && o.context == occurrence.context)
+        {
+            return None;
+        }
 
-        entry.last_seen = Utc::now();
         entry.total_occurrences += 1;
-        entry.unique_files.insert(file_path.to_string());
+        entry.unique_files.insert(occurrence.file_path.clone());
         entry.occurrences.push(occurrence);
 
         // Limit occurrences per string to prevent memory explosion
-        if entry.occurrences.len() > self.max_occurrences_per_string {
-            entry.occurrences.remove(0);
-        }
+        Self::truncate_occurrences(entry, self.max_occurrences_per_string);
 
-        Ok(())
+        let notify = inserted
+            && entry.is_suspicious
+            && !self
+                .suspicious_callbacks
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .is_empty();
+        notify.then(|| entry.clone())
+    }
+
+    /// Analyze and categorize strings without tracking them
+    ///
+    /// Results carry the same values `track_string` would store for a new entry, except
+    /// for the category derived from the occurrence context.
+    pub fn analyze_batch(&self, strings: &[String]) -> Vec<StringAnalysisResult> {
+        strings.iter().map(|s| self.analyze_value(s)).collect()
+    }
+
+    /// Run the analyzer and categorizer over a single value
+    fn analyze_value(&self, value: &str) -> StringAnalysisResult {
+        let analysis = self.analyzer.analyze(value);
+        let categorized = self.categorizer.categorize(value);
+
+        let mut categories =
+            HashSet::with_capacity(categorized.len() + analysis.categories.len() + 1);
+        categories.extend(categorized.into_iter().map(|c| c.name));
+        categories.extend(analysis.categories);
+
+        let suspicion_score = analysis
+            .suspicious_indicators
+            .iter()
+            .map(|i| u32::from(i.severity))
+            .sum();
+        let (suspicion_score, is_suspicious) =
+            self.apply_severity_overrides(&categories, suspicion_score, analysis.is_suspicious);
+
+        StringAnalysisResult {
+            value: value.to_string(),
+            entropy: analysis.entropy,
+            byte_entropy: analysis.byte_entropy,
+            categories,
+            is_suspicious,
+            suspicion_score,
+            suspicious_indicators: analysis.suspicious_indicators,
+        }
     }
 
     /// Track multiple strings from results
+    ///
+    /// The context of each string is derived from its categories (URL, path, registry,
+    /// library, command, otherwise a plain file string).
     pub fn track_strings_from_results(
         &self,
         strings: &[String],
@@ -294,99 +1095,170 @@ impl StringTracker {
         file_hash: &str,
         tool_name: &str,
     ) -> Result<()> {
-        for string in strings {
+        self.track_strings_from_results_with(
+            strings,
+            file_path,
+            file_hash,
+            tool_name,
+            Self::default_context,
+        )
+    }
+
+    /// Track multiple strings from results, deriving each context with `derive`
+    ///
+    /// `derive` receives the string and the categories assigned by the categorizer.
+    pub fn track_strings_from_results_with<F>(
+        &self,
+        strings: &[String],
+        file_path: &str,
+        file_hash: &str,
+        tool_name: &str,
+        derive: F,
+    ) -> Result<()>
+    where
+        F: Fn(&str, &[StringCategory]) -> StringContext,
+    {
+        for string in strings.iter().filter(|s| !self.is_ignored(s)) {
             // Categorize the string using the categorizer
             let categories = self.categorizer.categorize(string);
-
-            // Determine context based on categories
-            let context = if categories.iter().any(|c| c.name == "url") {
-                let protocol = string.split("://").next().map(|p| p.to_string());
-                StringContext::Url { protocol }
-            } else if categories.iter().any(|c| c.name == "path") {
-                let path_type = if string.contains("\\Windows") || string.contains("/usr") {
-                    "system"
-                } else if string.contains("\\Temp") || string.contains("/tmp") {
-                    "temp"
-                } else {
-                    "general"
-                };
-                StringContext::Path {
-                    path_type: path_type.to_string(),
-                }
-            } else if categories.iter().any(|c| c.name == "registry") {
-                let hive = string.split('\\').next().map(|h| h.to_string());
-                StringContext::Registry { hive }
-            } else if categories.iter().any(|c| c.name == "library") {
-                StringContext::Import {
-                    library: string.to_string(),
-                }
-            } else if categories.iter().any(|c| c.name == "command") {
-                StringContext::Command {
-                    command_type: "shell".to_string(),
-                }
-            } else {
-                StringContext::FileString { offset: None }
-            };
+            let context = derive(string, &categories);
 
             self.track_string(string, file_path, file_hash, tool_name, context)?;
+
+            // Track the payload of encoded PowerShell commands alongside the command
+            if let Some(decoded) = decode_powershell_command(string) {
+                self.track_string(
+                    &decoded,
+                    file_path,
+                    file_hash,
+                    tool_name,
+                    StringContext::Command {
+                        command_type: "powershell_decoded".to_string(),
+                    },
+                )?;
+            }
         }
         Ok(())
     }
 
-    /// Get statistics about tracked strings
-    pub fn get_statistics(&self, filter: Option<&StringFilter>) -> StringStatistics {
-        let entries = self.entries.lock().unwrap();
-
-        let filtered_entries: Vec<_> = entries
-            .values()
-            .filter(|entry| self.matches_filter(entry, filter))
-            .collect();
+    /// Default category-to-context mapping used by `track_strings_from_results`
+    ///
+    /// Strings categorized as commands get a `powershell` command type when they look
+    /// like a PowerShell invocation, and `shell` otherwise.
+    fn default_context(string: &str, categories: &[StringCategory]) -> StringContext {
+        if categories.iter().any(|c| c.name == "url") {
+            let protocol = string
+                .split_once("://")
+                .map(|(scheme, _)| scheme.to_lowercase())
+                .filter(|scheme| !scheme.is_empty());
+            StringContext::Url { protocol }
+        } else if categories.iter().any(|c| c.name == "path") {
+            let path_type = if string.contains("\\Windows") || string.contains("/usr") {
+                "system"
+            } else if string.contains("\\Temp") || string.contains("/tmp") {
+                "temp"
+            } else {
+                "general"
+            };
+            StringContext::Path {
+                path_type: path_type.to_string(),
+            }
+        } else if categories.iter().any(|c| c.name == "registry") {
+            let hive = string.split('\\').next().map(|h| h.to_string());
+            StringContext::Registry { hive }
+        } else if categories.iter().any(|c| c.name == "library") {
+            StringContext::Import {
+                library: string.to_string(),
+            }
+        } else if categories.iter().any(|c| c.name == "command") {
+            let command_type = if POWERSHELL_REGEX.is_match(string) {
+                "powershell"
+            } else {
+                "shell"
+            };
+            StringContext::Command {
+                command_type: command_type.to_string(),
+            }
+        } else {
+            StringContext::FileString { offset: None }
+        }
+    }
 
-        let total_unique_strings = filtered_entries.len();
-        let total_occurrences: usize = filtered_entries.iter().map(|e| e.total_occurrences).sum();
+    /// Get statistics about tracked strings
+    ///
+    /// A filter whose `regex_pattern` does not compile yields empty statistics, which
+    /// cannot be told apart from a filter that matched nothing. Use
+    /// [`try_get_statistics`](Self::try_get_statistics) or [`StringFilter::validate`]
+    /// when the filter comes from user input.
+    pub fn get_statistics(&self, filter: Option<&StringFilter>) -> StringStatistics {
+        let Ok(filter) = CompiledExpr::compile(filter) else {
+            return StringStatistics::default();
+        };
+        let entries = self.lock_entries();
+        self.compute_statistics(&entries, filter.as_ref())
+    }
 
-        let total_files_analyzed: HashSet<_> = filtered_entries
-            .iter()
-            .flat_map(|e| e.unique_files.iter())
-            .collect();
+    /// Get statistics about tracked strings, failing if the filter is invalid
+    pub fn try_get_statistics(&self, filter: Option<&StringFilter>) -> Result<StringStatistics> {
+        let filter = CompiledExpr::compile(filter)?;
+        let entries = self.try_lock_entries()?;
+        Ok(self.compute_statistics(&entries, filter.as_ref()))
+    }
 
-        // Most common strings
-        let mut most_common: Vec<_> = filtered_entries
-            .iter()
-            .map(|e| (e.value.clone(), e.total_occurrences))
-            .collect();
-        most_common.sort_by(|a, b| b.1.cmp(&a.1));
-        most_common.truncate(100);
+    /// Get statistics about the tracked strings matching a filter expression
+    ///
+    /// Flat filters passed to [`get_statistics`](Self::get_statistics) behave like a
+    /// single [`FilterExpr::Leaf`]. Fails if any filter in the expression is invalid.
+    pub fn get_statistics_expr(&self, expr: &FilterExpr) -> Result<StringStatistics> {
+        let expr = CompiledExpr::new(expr)?;
+        let entries = self.try_lock_entries()?;
+        Ok(self.compute_statistics(&entries, Some(&expr)))
+    }
 
-        // Suspicious strings
-        let suspicious_strings: Vec<_> = filtered_entries
-            .iter()
-            .filter(|e| e.is_suspicious)
-            .map(|e| e.value.clone())
-            .take(50)
-            .collect();
+    /// Compute statistics over `entries` for the entries matching `filter`
+    pub(crate) fn compute_statistics(
+        &self,
+        entries: &HashMap<String, StringEntry>,
+        filter: Option<&CompiledExpr>,
+    ) -> StringStatistics {
+        let mut total_unique_strings = 0;
+        let mut total_occurrences = 0;
+        let mut total_files_analyzed = HashSet::new();
+        let mut category_distribution = HashMap::new();
+        let mut category_severity = HashMap::new();
+        let mut length_distribution = HashMap::new();
 
-        // High entropy strings
-        let mut high_entropy_strings: Vec<_> = filtered_entries
-            .iter()
-            .filter(|e| e.entropy > 4.0)
-            .map(|e| (e.value.clone(), e.entropy))
-            .collect();
-        high_entropy_strings.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        high_entropy_strings.truncate(50);
+        // Top-N lists are kept in bounded heaps; ties are broken on the value so results
+        // do not depend on HashMap order
+        let mut most_common = TopN::new(100);
+        let mut suspicious_strings = TopN::new(50);
+        let mut high_entropy_strings = TopN::new(50);
 
-        // Category distribution
-        let mut category_distribution = HashMap::new();
-        for entry in &filtered_entries {
+        for entry in entries
+            .values()
+            .filter(|entry| self.matches_filter(entry, filter))
+        {
+            total_unique_strings += 1;
+            total_occurrences += entry.total_occurrences;
+            total_files_analyzed.extend(entry.unique_files.iter());
+
+            most_common.push((entry.total_occurrences, Reverse(entry.value.as_str())));
+            if entry.is_suspicious {
+                suspicious_strings.push(Reverse(entry.value.as_str()));
+            }
+            if entry.entropy > 4.0 {
+                high_entropy_strings.push((TotalF64(entry.entropy), Reverse(entry.value.as_str())));
+            }
+
+            // Category distribution and the riskiest string per category
             for category in &entry.categories {
                 *category_distribution.entry(category.clone()).or_insert(0) += 1;
+                let severity = category_severity.entry(category.clone()).or_insert(0);
+                *severity = (*severity).max(entry.suspicion_score);
             }
-        }
 
-        // Length distribution
-        let mut length_distribution = HashMap::new();
-        for entry in &filtered_entries {
-            let len_bucket = match entry.value.len() {
+            // Length distribution
+            let len_bucket = match entry.char_len() {
                 0..=10 => "0-10",
                 11..=20 => "11-20",
                 21..=50 => "21-50",
@@ -399,6 +1271,22 @@ impl StringTracker {
                 .or_insert(0) += 1;
         }
 
+        let most_common = most_common
+            .into_sorted_vec()
+            .into_iter()
+            .map(|(count, Reverse(value))| (value.to_string(), count))
+            .collect();
+        let suspicious_strings = suspicious_strings
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(value)| value.to_string())
+            .collect();
+        let high_entropy_strings = high_entropy_strings
+            .into_sorted_vec()
+            .into_iter()
+            .map(|(TotalF64(entropy), Reverse(value))| (value.to_string(), entropy))
+            .collect();
+
         StringStatistics {
             total_unique_strings,
             total_occurrences,
@@ -408,13 +1296,25 @@ impl StringTracker {
             high_entropy_strings,
             category_distribution,
             length_distribution,
+            category_severity,
         }
     }
 
-    fn matches_filter(&self, entry: &StringEntry, filter: Option<&StringFilter>) -> bool {
-        let Some(f) = filter else {
-            return true;
-        };
+    fn matches_filter(&self, entry: &StringEntry, filter: Option<&CompiledExpr>) -> bool {
+        filter.map_or(true, |expr| self.matches_expr(entry, expr))
+    }
+
+    fn matches_expr(&self, entry: &StringEntry, expr: &CompiledExpr) -> bool {
+        match expr {
+            CompiledExpr::Leaf(compiled) => self.matches_leaf(entry, compiled),
+            CompiledExpr::And(exprs) => exprs.iter().all(|e| self.matches_expr(entry, e)),
+            CompiledExpr::Or(exprs) => exprs.iter().any(|e| self.matches_expr(entry, e)),
+            CompiledExpr::Not(expr) => !self.matches_expr(entry, expr),
+        }
+    }
+
+    fn matches_leaf(&self, entry: &StringEntry, compiled: &CompiledFilter) -> bool {
+        let f = compiled.filter;
 
         if let Some(min) = f.min_occurrences {
             if entry.total_occurrences < min {
@@ -428,26 +1328,29 @@ impl StringTracker {
             }
         }
 
-        if let Some(min) = f.min_length {
-            if entry.value.len() < min {
+        if f.min_length.is_some() || f.max_length.is_some() {
+            let len = entry.char_len();
+            if f.min_length.is_some_and(|min| len < min)
+                || f.max_length.is_some_and(|max| len > max)
+            {
                 return false;
             }
         }
 
-        if let Some(max) = f.max_length {
-            if entry.value.len() > max {
+        if let Some(ref categories) = compiled.categories {
+            if !categories.iter().any(|c| c.matches(&entry.categories)) {
                 return false;
             }
         }
 
-        if let Some(ref categories) = f.categories {
-            if !categories.iter().any(|c| entry.categories.contains(c)) {
+        if let Some(ref file_hashes) = f.file_hashes {
+            if !file_hashes.iter().any(|h| entry.unique_files.contains(h)) {
                 return false;
             }
         }
 
-        if let Some(ref file_hashes) = f.file_hashes {
-            if !file_hashes.iter().any(|h| entry.unique_files.contains(h)) {
+        if let Some(ref tools) = f.tools {
+            if !tools.iter().any(|t| entry.unique_tools.contains(t)) {
                 return false;
             }
         }
@@ -458,11 +1361,9 @@ impl StringTracker {
             }
         }
 
-        if let Some(ref pattern) = f.regex_pattern {
-            if let Ok(re) = regex::Regex::new(pattern) {
-                if !re.is_match(&entry.value) {
-                    return false;
-                }
+        if let Some(ref re) = compiled.regex {
+            if !re.is_match(&entry.value) {
+                return false;
             }
         }
 
@@ -478,23 +1379,287 @@ impl StringTracker {
             }
         }
 
+        if let Some(min_score) = f.min_suspicion_score {
+            if entry.suspicion_score < min_score {
+                return false;
+            }
+        }
+
+        if let Some(max_score) = f.max_suspicion_score {
+            if entry.suspicion_score > max_score {
+                return false;
+            }
+        }
+
+        if let Some(min_ratio) = f.min_printable_ratio {
+            if entry.printable_ratio < min_ratio {
+                return false;
+            }
+        }
+
+        if let Some(max_ratio) = f.max_printable_ratio {
+            if entry.printable_ratio > max_ratio {
+                return false;
+            }
+        }
+
         true
     }
 
     /// Get detailed information about a specific string
     pub fn get_string_details(&self, value: &str) -> Option<StringEntry> {
-        let entries = self.entries.lock().unwrap();
-        entries.get(value).cloned()
+        let entries = self.lock_entries();
+        entries.get(self.entry_key(value).as_ref()).cloned()
+    }
+
+    /// Inspect a single tracked string under the lock without cloning it
+    ///
+    /// The closure receives `None` if the string is not tracked. Prefer this over
+    /// [`get_string_details`](Self::get_string_details) when only a few fields are needed.
+    pub fn with_entry<R>(&self, value: &str, f: impl FnOnce(Option<&StringEntry>) -> R) -> R {
+        let entries = self.lock_entries();
+        f(entries.get(self.entry_key(value).as_ref()))
+    }
+
+    /// Visit every tracked string under the lock without cloning
+    ///
+    /// The lock is held for the duration of the iteration, so the closure must not
+    /// call back into this tracker.
+    pub fn for_each_entry<F: FnMut(&StringEntry)>(&self, mut f: F) {
+        let entries = self.lock_entries();
+        for entry in entries.values() {
+            f(entry);
+        }
+    }
+
+    /// Get every retained occurrence within `range` (inclusive), ordered by timestamp
+    ///
+    /// Occurrences sharing a timestamp are ordered by string value.
+    pub fn occurrences_in_range(&self, range: DateTimeRange) -> Vec<(String, StringOccurrence)> {
+        let (start, end) = range;
+        let entries = self.lock_entries();
+
+        let mut occurrences: Vec<_> = entries
+            .values()
+            .flat_map(|entry| {
+                entry
+                    .occurrences
+                    .iter()
+                    .filter(|o| o.timestamp >= start && o.timestamp <= end)
+                    .map(|o| (entry.value.clone(), o.clone()))
+            })
+            .collect();
+
+        occurrences.sort_by(|a, b| {
+            a.1.timestamp
+                .cmp(&b.1.timestamp)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        occurrences
+    }
+
+    /// Strings found in `file_path` at file offsets within `offset ± window`
+    ///
+    /// Returns `(value, offset)` for each retained `FileString` occurrence in range, ordered
+    /// by distance from `offset`, then by offset and value. A string seen at several nearby
+    /// offsets is listed once per offset; occurrences without an offset are skipped.
+    pub fn strings_near(
+        &self,
+        file_path: &str,
+        offset: usize,
+        window: usize,
+    ) -> Vec<(String, usize)> {
+        let entries = self.lock_entries();
+
+        let mut near: Vec<_> = entries
+            .values()
+            .filter(|entry| entry.unique_files.contains(file_path))
+            .flat_map(|entry| {
+                entry
+                    .occurrences
+                    .iter()
+                    .filter_map(move |o| match o.context {
+                        StringContext::FileString {
+                            offset: Some(found),
+                        } if o.file_path == file_path && found.abs_diff(offset) <= window => {
+                            Some((entry.value.clone(), found))
+                        }
+                        _ => None,
+                    })
+            })
+            .collect();
+
+        near.sort_by(|a, b| {
+            a.1.abs_diff(offset)
+                .cmp(&b.1.abs_diff(offset))
+                .then_with(|| a.1.cmp(&b.1))
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        near.dedup();
+        near
+    }
+
+    /// Rate at which a string has been appearing, in occurrences per second
+    ///
+    /// Counts the retained occurrences timestamped within `window` up to and including
+    /// the string's `last_seen`, divided by the window length. Returns `None` for unknown
+    /// strings and `0.0` when no occurrences fall in the window or the window is not positive.
+    pub fn string_velocity(&self, value: &str, window: Duration) -> Option<f64> {
+        let entries = self.lock_entries();
+        let entry = entries.get(self.entry_key(value).as_ref())?;
+
+        let window_secs = window
+            .num_nanoseconds()
+            .map(|n| n as f64 / 1e9)
+            .unwrap_or_else(|| window.num_milliseconds() as f64 / 1e3);
+        if window_secs <= 0.0 {
+            return Some(0.0);
+        }
+
+        let start = entry
+            .last_seen
+            .checked_sub_signed(window)
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let count = entry
+            .occurrences
+            .iter()
+            .filter(|o| o.timestamp >= start && o.timestamp <= entry.last_seen)
+            .count();
+
+        Some(count as f64 / window_secs)
+    }
+
+    /// Histogram of a string's retained occurrences over time
+    ///
+    /// Buckets of width `bucket` start at the string's `first_seen` and cover up to its
+    /// `last_seen`; each is returned as `(bucket_start, count)` in chronological order,
+    /// including empty buckets. Returns an empty vec for unknown strings, a non-positive
+    /// bucket width, or when more than [`MAX_TIMELINE_BUCKETS`](Self::MAX_TIMELINE_BUCKETS)
+    /// buckets would be needed.
+    pub fn occurrence_timeline(
+        &self,
+        value: &str,
+        bucket: Duration,
+    ) -> Vec<(DateTime<Utc>, usize)> {
+        let Some(bucket_nanos) = bucket.num_nanoseconds().filter(|n| *n > 0) else {
+            return Vec::new();
+        };
+
+        let entries = self.lock_entries();
+        let Some(entry) = entries.get(self.entry_key(value).as_ref()) else {
+            return Vec::new();
+        };
+
+        let span_nanos = (entry.last_seen - entry.first_seen)
+            .num_nanoseconds()
+            .unwrap_or(i64::MAX)
+            .max(0);
+        let bucket_count = (span_nanos / bucket_nanos) as usize + 1;
+        if bucket_count > Self::MAX_TIMELINE_BUCKETS {
+            return Vec::new();
+        }
+
+        let mut counts = vec![0usize; bucket_count];
+        for occurrence in &entry.occurrences {
+            let offset = (occurrence.timestamp - entry.first_seen)
+                .num_nanoseconds()
+                .unwrap_or(i64::MAX)
+                .max(0);
+            let index = ((offset / bucket_nanos) as usize).min(bucket_count - 1);
+            counts[index] += 1;
+        }
+
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| (entry.first_seen + bucket * i as i32, count))
+            .collect()
+    }
+
+    /// Get strings belonging to `category`, most frequent first
+    ///
+    /// Ties are broken by string value. Cheaper than a category filter on
+    /// `get_statistics` since no distributions are computed.
+    pub fn get_strings_by_category(&self, category: &str, limit: usize) -> Vec<StringEntry> {
+        let entries = self.lock_entries();
+
+        let mut matches: Vec<_> = entries
+            .values()
+            .filter(|e| e.categories.contains(category))
+            .collect();
+        matches.sort_by(|a, b| {
+            b.total_occurrences
+                .cmp(&a.total_occurrences)
+                .then_with(|| a.value.cmp(&b.value))
+        });
+
+        matches.into_iter().take(limit).cloned().collect()
+    }
+
+    /// Each string in `category` with the context it was first seen in, ordered by value
+    ///
+    /// The context comes from the string's earliest retained occurrence. If the occurrence
+    /// at `first_seen` has been evicted by the occurrence cap, the most recent retained
+    /// occurrence is used instead. Strings with no retained occurrences are skipped.
+    pub fn category_first_contexts(&self, category: &str) -> Vec<(String, StringContext)> {
+        let entries = self.lock_entries();
+
+        let mut contexts: Vec<_> = entries
+            .values()
+            .filter(|e| e.categories.contains(category))
+            .filter_map(|e| {
+                let earliest = e.occurrences.iter().min_by_key(|o| o.timestamp)?;
+                let occurrence = if earliest.timestamp <= e.first_seen {
+                    earliest
+                } else {
+                    e.occurrences.iter().max_by_key(|o| o.timestamp)?
+                };
+                Some((e.value.clone(), occurrence.context.clone()))
+            })
+            .collect();
+        contexts.sort_by(|a, b| a.0.cmp(&b.0));
+        contexts
+    }
+
+    /// Rank suspicious strings by suspicion score, highest first
+    ///
+    /// Ties are broken by `total_occurrences`, then entropy, then string value.
+    pub fn top_suspicious(&self, limit: usize) -> Vec<(String, u32)> {
+        let entries = self.lock_entries();
+
+        let mut ranked: Vec<_> = entries.values().filter(|e| e.is_suspicious).collect();
+        ranked.sort_by(|a, b| {
+            b.suspicion_score
+                .cmp(&a.suspicion_score)
+                .then_with(|| b.total_occurrences.cmp(&a.total_occurrences))
+                .then_with(|| b.entropy.total_cmp(&a.entropy))
+                .then_with(|| a.value.cmp(&b.value))
+        });
+
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(|e| (e.value.clone(), e.suspicion_score))
+            .collect()
     }
 
     /// Search for strings matching a query
     pub fn search_strings(&self, query: &str, limit: usize) -> Vec<StringEntry> {
+        let entries = self.lock_entries();
+        Self::search_entries(&entries, query, limit)
+    }
+
+    /// Case-insensitive substring search over `entries`, most frequent first
+    pub(crate) fn search_entries(
+        entries: &HashMap<String, StringEntry>,
+        query: &str,
+        limit: usize,
+    ) -> Vec<StringEntry> {
         // Return empty results for empty queries
         if query.trim().is_empty() {
             return Vec::new();
         }
 
-        let entries = self.entries.lock().unwrap();
         let query_lower = query.to_lowercase();
 
         let mut results: Vec<_> = entries
@@ -503,75 +1668,866 @@ impl StringTracker {
             .cloned()
             .collect();
 
-        results.sort_by(|a, b| b.total_occurrences.cmp(&a.total_occurrences));
+        results.sort_by_key(|e| std::cmp::Reverse(e.total_occurrences));
         results.truncate(limit);
         results
     }
 
+    /// Search for strings matching a query, ranked by match quality
+    ///
+    /// Match types score exact > prefix > substring > fuzzy (within
+    /// `max_edit_distance` edits of the whole string), blended with the occurrence count
+    /// according to `occurrence_weight`. Results are ordered by descending score.
+    pub fn search_strings_fuzzy(
+        &self,
+        query: &str,
+        limit: usize,
+        options: &SearchOptions,
+    ) -> Vec<(StringEntry, f64)> {
+        if query.trim().is_empty() {
+            return Vec::new();
+        }
+
+        let normalize = |s: &str| {
+            if options.case_sensitive {
+                s.to_string()
+            } else {
+                s.to_lowercase()
+            }
+        };
+        let query = normalize(query);
+        let weight = options.occurrence_weight.clamp(0.0, 1.0);
+
+        let entries = self.lock_entries();
+        let mut results: Vec<_> = entries
+            .values()
+            .filter_map(|entry| {
+                let value = normalize(&entry.value);
+                let match_score = if value == query {
+                    1.0
+                } else if value.starts_with(&query) {
+                    0.8
+                } else if value.contains(&query) {
+                    0.6
+                } else {
+                    let distance =
+                        bounded_edit_distance(&query, &value, options.max_edit_distance)?;
+                    0.4 * (1.0 - distance as f64 / (options.max_edit_distance + 1) as f64)
+                };
+
+                let occurrences = entry.total_occurrences as f64;
+                let occurrence_score = occurrences / (occurrences + 10.0);
+                let score = match_score * (1.0 - weight) + occurrence_score * weight;
+                Some((entry, score))
+            })
+            .collect();
+
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.value.cmp(&b.0.value)));
+        results
+            .into_iter()
+            .take(limit)
+            .map(|(entry, score)| (entry.clone(), score))
+            .collect()
+    }
+
     /// Get strings related to a given string
     pub fn get_related_strings(&self, value: &str, limit: usize) -> StringScoreVec {
-        let entries = self.entries.lock().unwrap();
+        self.get_related_strings_weighted(value, limit, &SimilarityWeights::default())
+    }
 
-        let Some(target_entry) = entries.get(value) else {
+    /// Get strings related to a given string, scoring similarity with `weights`
+    ///
+    /// Results above `weights.cutoff` are ordered by similarity (highest first).
+    pub fn get_related_strings_weighted(
+        &self,
+        value: &str,
+        limit: usize,
+        weights: &SimilarityWeights,
+    ) -> StringScoreVec {
+        let entries = self.lock_entries();
+
+        let key = self.entry_key(value);
+        let Some(target_entry) = entries.get(key.as_ref()) else {
             return vec![];
         };
 
         let mut similarities: Vec<_> = entries
             .iter()
-            .filter(|(k, _)| *k != value)
-            .map(|(k, v)| {
-                let similarity = self.calculate_similarity(target_entry, v);
-                (k.clone(), similarity)
+            .filter(|(k, _)| k.as_str() != key.as_ref())
+            .map(|(_, v)| {
+                let similarity = self.calculate_similarity(target_entry, v, weights);
+                (v.value.clone(), similarity)
             })
-            .filter(|(_, sim)| *sim > 0.3)
+            .filter(|(_, sim)| *sim > weights.cutoff)
             .collect();
 
-        similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        similarities.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         similarities.truncate(limit);
         similarities
     }
 
-    fn calculate_similarity(&self, a: &StringEntry, b: &StringEntry) -> f64 {
+    fn calculate_similarity(
+        &self,
+        a: &StringEntry,
+        b: &StringEntry,
+        weights: &SimilarityWeights,
+    ) -> f64 {
         let mut score = 0.0;
+        // Sum of the weights of the factors that apply to this pair
         let mut factors = 0.0;
 
         // Shared files
         let shared_files: HashSet<_> = a.unique_files.intersection(&b.unique_files).collect();
         if !shared_files.is_empty() {
-            score +=
-                shared_files.len() as f64 / a.unique_files.len().min(b.unique_files.len()) as f64;
-            factors += 1.0;
+            score += weights.files * shared_files.len() as f64
+                / a.unique_files.len().min(b.unique_files.len()) as f64;
+            factors += weights.files;
         }
 
         // Shared categories
         let shared_categories: HashSet<_> = a.categories.intersection(&b.categories).collect();
         if !shared_categories.is_empty() {
-            score +=
-                shared_categories.len() as f64 / a.categories.len().min(b.categories.len()) as f64;
-            factors += 1.0;
+            score += weights.categories * shared_categories.len() as f64
+                / a.categories.len().min(b.categories.len()) as f64;
+            factors += weights.categories;
         }
 
         // Similar entropy
         let entropy_diff = (a.entropy - b.entropy).abs();
-        if entropy_diff < 0.5 {
-            score += 1.0 - (entropy_diff / 0.5);
-            factors += 1.0;
+        if entropy_diff < weights.entropy_window {
+            score += weights.entropy * (1.0 - (entropy_diff / weights.entropy_window));
+            factors += weights.entropy;
         }
 
         // Similar length
-        let len_a = a.value.len() as f64;
-        let len_b = b.value.len() as f64;
+        let len_a = a.char_len() as f64;
+        let len_b = b.char_len() as f64;
         let len_ratio = len_a.min(len_b) / len_a.max(len_b);
-        score += len_ratio;
-        factors += 1.0;
+        score += weights.length * len_ratio;
+        factors += weights.length;
 
         if factors > 0.0 { score / factors } else { 0.0 }
     }
 
+    /// Track strings from JSON Lines input, one record per line
+    ///
+    /// Each line is an object with `value`, `file_path`, `file_hash`, `tool_name` and
+    /// `context` (a serialized [`StringContext`]). Input is read line by line. Blank lines
+    /// are ignored and malformed lines are recorded in the report without stopping the
+    /// stream; only a read error aborts ingestion.
+    pub fn ingest_jsonl<R: BufRead>(&self, reader: R) -> Result<IngestReport> {
+        let mut report = IngestReport::default();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str::<IngestRecord>(&line) {
+                Ok(record) => {
+                    self.track_string(
+                        &record.value,
+                        &record.file_path,
+                        &record.file_hash,
+                        &record.tool_name,
+                        record.context,
+                    )?;
+                    report.succeeded += 1;
+                }
+                Err(e) => {
+                    report.failed += 1;
+                    report.failures.push(IngestFailure {
+                        line: index + 1,
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Export tracked strings as CSV, one row per string matching the filter
+    ///
+    /// Columns: value, total_occurrences, unique_file_count, first_seen, last_seen,
+    /// entropy, is_suspicious, categories (semicolon-joined). Rows are ordered by value.
+    pub fn export_csv<W: Write>(&self, mut writer: W, filter: Option<&StringFilter>) -> Result<()> {
+        let filter = CompiledExpr::compile(filter)?;
+        let entries = self.try_lock_entries()?;
+
+        let mut rows: Vec<_> = entries
+            .values()
+            .filter(|entry| self.matches_filter(entry, filter.as_ref()))
+            .collect();
+        rows.sort_by(|a, b| a.value.cmp(&b.value));
+
+        writeln!(
+            writer,
+            "value,total_occurrences,unique_file_count,first_seen,last_seen,entropy,is_suspicious,categories"
+        )?;
+
+        for entry in rows {
+            let mut categories: Vec<_> = entry.categories.iter().map(String::as_str).collect();
+            categories.sort_unstable();
+
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{}",
+                csv_field(&entry.value),
+                entry.total_occurrences,
+                entry.unique_files.len(),
+                entry.first_seen.to_rfc3339(),
+                entry.last_seen.to_rfc3339(),
+                entry.entropy,
+                entry.is_suspicious,
+                csv_field(&categories.join(";")),
+            )?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Produce findings for suspicious strings matching the filter
+    ///
+    /// Fails if the filter is invalid.
+    ///
+    /// Finding ids only depend on the string value and the set of file hashes in the
+    /// evidence, so re-scanning the same files yields the same ids. Findings are ordered by
+    /// severity (highest first) and then by value.
+    pub fn findings(&self, filter: Option<&StringFilter>) -> Result<Vec<Finding>> {
+        let filter = CompiledExpr::compile(filter)?;
+        let entries = self.try_lock_entries()?;
+
+        let mut findings: Vec<_> = entries
+            .values()
+            .filter(|entry| entry.is_suspicious && self.matches_filter(entry, filter.as_ref()))
+            .map(|entry| {
+                let mut techniques: Vec<_> = entry
+                    .suspicious_indicators
+                    .iter()
+                    .map(|i| i.pattern_name.clone())
+                    .collect();
+                techniques.sort();
+                techniques.dedup();
+
+                let file_hashes: std::collections::BTreeSet<_> = entry
+                    .occurrences
+                    .iter()
+                    .map(|o| o.file_hash.as_str())
+                    .collect();
+                let id = stable_hash(std::iter::once(entry.value.as_str()).chain(file_hashes));
+
+                Finding {
+                    id: format!("{:016x}", id),
+                    value: entry.value.clone(),
+                    techniques,
+                    severity: entry
+                        .suspicious_indicators
+                        .iter()
+                        .map(|i| i.severity)
+                        .max()
+                        .unwrap_or(0),
+                    evidence: entry.occurrences.clone(),
+                }
+            })
+            .collect();
+
+        findings.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then_with(|| a.value.cmp(&b.value))
+        });
+        Ok(findings)
+    }
+
+    /// Compute a single 0-100 triage score for a file using the default weights
+    pub fn file_threat_score(&self, hash: &str) -> f64 {
+        self.file_threat_score_with(hash, &ThreatScoreWeights::default())
+    }
+
+    /// Compute a single 0-100 triage score for a file using custom weights
+    ///
+    /// Only strings with a retained occurrence carrying `hash` are considered. Files
+    /// without suspicious strings score 0.
+    pub fn file_threat_score_with(&self, hash: &str, weights: &ThreatScoreWeights) -> f64 {
+        let entries = self.lock_entries();
+
+        let suspicious: Vec<_> = entries
+            .values()
+            .filter(|e| e.is_suspicious)
+            .filter(|e| e.occurrences.iter().any(|o| o.file_hash == hash))
+            .collect();
+
+        if suspicious.is_empty() {
+            return 0.0;
+        }
+
+        let count = suspicious.len() as f64;
+        let categories: HashSet<_> = suspicious.iter().flat_map(|e| &e.categories).collect();
+        let diversity = categories.len() as f64;
+        let max_severity = suspicious
+            .iter()
+            .flat_map(|e| &e.suspicious_indicators)
+            .map(|i| i.severity)
+            .max()
+            .unwrap_or(0);
+        let rarity = suspicious
+            .iter()
+            .map(|e| 1.0 / e.unique_files.len().max(1) as f64)
+            .sum::<f64>()
+            / count;
+
+        let saturation = weights.saturation.max(f64::EPSILON);
+        let weighted = weights.suspicious_count * (count / (count + saturation))
+            + weights.category_diversity * (diversity / (diversity + saturation))
+            + weights.max_severity * (f64::from(max_severity.min(10)) / 10.0)
+            + weights.rarity * rarity;
+        let total_weight = weights.suspicious_count
+            + weights.category_diversity
+            + weights.max_severity
+            + weights.rarity;
+
+        if total_weight > 0.0 {
+            100.0 * weighted / total_weight
+        } else {
+            0.0
+        }
+    }
+
+    /// Pairs of strings found together in at least `min_shared_files` files
+    ///
+    /// Each edge is `(a, b, shared_files)` with `a < b`. Edges are ordered by shared file
+    /// count (highest first) and then by the pair. Pairs are counted per file from an
+    /// inverted file-to-strings index, so strings that never share a file cost nothing.
+    pub fn cooccurrence_edges(&self, min_shared_files: usize) -> Vec<(String, String, usize)> {
+        let entries = self.lock_entries();
+
+        let mut files: HashMap<&str, Vec<&str>> = HashMap::new();
+        for entry in entries.values() {
+            for file in &entry.unique_files {
+                files.entry(file).or_default().push(&entry.value);
+            }
+        }
+
+        let mut shared: HashMap<(&str, &str), usize> = HashMap::new();
+        for mut values in files.into_values() {
+            values.sort_unstable();
+            for (i, a) in values.iter().enumerate() {
+                for b in &values[i + 1..] {
+                    *shared.entry((a, b)).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let min_shared_files = min_shared_files.max(1);
+        let mut edges: Vec<_> = shared
+            .into_iter()
+            .filter(|(_, count)| *count >= min_shared_files)
+            .map(|((a, b), count)| (a.to_string(), b.to_string(), count))
+            .collect();
+        edges.sort_by(|x, y| y.2.cmp(&x.2).then_with(|| (&x.0, &x.1).cmp(&(&y.0, &y.1))));
+        edges
+    }
+
+    /// Group tracked strings into clusters of similar strings
+    ///
+    /// Two strings are linked when the Jaccard similarity of their character n-gram sets
+    /// reaches the threshold; clusters are the connected components of those links. Members
+    /// are sorted, and clusters are ordered by size (largest first) and then by first member.
+    pub fn cluster_strings(&self, options: ClusterOptions) -> Vec<Vec<String>> {
+        let mut values: Vec<String> = {
+            let entries = self.lock_entries();
+            entries.values().map(|entry| entry.value.clone()).collect()
+        };
+        values.sort();
+
+        let ngrams: Vec<HashSet<&str>> = values
+            .iter()
+            .map(|v| char_ngrams(v, options.ngram_size.max(1)))
+            .collect();
+
+        // Union-find over string indices
+        let mut parent: Vec<usize> = (0..values.len()).collect();
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        for i in 0..values.len() {
+            for j in (i + 1)..values.len() {
+                let (a, b) = (&ngrams[i], &ngrams[j]);
+                let union = a.union(b).count();
+                if union == 0 {
+                    continue;
+                }
+                let similarity = a.intersection(b).count() as f64 / union as f64;
+                if similarity >= options.similarity_threshold {
+                    let (root_i, root_j) = (find(&mut parent, i), find(&mut parent, j));
+                    if root_i != root_j {
+                        parent[root_j.max(root_i)] = root_i.min(root_j);
+                    }
+                }
+            }
+        }
+
+        let mut groups: HashMap<usize, Vec<String>> = HashMap::new();
+        for (i, value) in values.iter().enumerate() {
+            let root = find(&mut parent, i);
+            groups.entry(root).or_default().push(value.clone());
+        }
+
+        let mut clusters: Vec<_> = groups
+            .into_values()
+            .filter(|members| members.len() >= options.min_cluster_size.max(1))
+            .collect();
+        // Members are already sorted because indices follow the sorted values
+        clusters.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(&b[0])));
+        clusters
+    }
+
+    /// Compare this tracker (the baseline) against `other`
+    ///
+    /// Each tracker is read under a single lock, one after the other, so the two are
+    /// never locked at the same time. Comparing a tracker with itself or a clone of
+    /// itself yields an empty diff. Entries are matched by key, so with a key normalizer
+    /// variants of a string match; the diff reports each entry's tracked `value`.
+    pub fn diff(&self, other: &StringTracker) -> TrackerDiff {
+        let other_counts: HashMap<String, (String, usize)> = other
+            .lock_entries()
+            .iter()
+            .map(|(key, entry)| (key.clone(), (entry.value.clone(), entry.total_occurrences)))
+            .collect();
+
+        let mut diff = TrackerDiff::default();
+        let entries = self.lock_entries();
+        for (key, entry) in entries.iter() {
+            match other_counts.get(key) {
+                None => diff.removed.push(entry.value.clone()),
+                Some(&(_, count)) if count != entry.total_occurrences => {
+                    let delta = count as isize - entry.total_occurrences as isize;
+                    diff.changed.push((entry.value.clone(), delta));
+                }
+                Some(_) => {}
+            }
+        }
+        diff.added = other_counts
+            .into_iter()
+            .filter(|(key, _)| !entries.contains_key(key))
+            .map(|(_, (value, _))| value)
+            .collect();
+        drop(entries);
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort();
+        diff
+    }
+
+    /// Snapshot all tracked entries
+    pub fn state(&self) -> TrackerState {
+        let entries = self.lock_entries();
+        let mut entries: Vec<_> = entries.values().cloned().collect();
+        entries.sort_by(|a, b| a.value.cmp(&b.value));
+        TrackerState {
+            version: TrackerState::VERSION,
+            entries,
+        }
+    }
+
+    /// Replace all tracked entries with those in `state`
+    pub fn restore_state(&self, state: TrackerState) -> Result<()> {
+        let mut restored: EntryStore = state
+            .entries
+            .into_iter()
+            .map(|entry| (self.entry_key(&entry.value).into_owned(), entry))
+            .collect();
+        if self.max_entries.is_some() {
+            restored.track_recency();
+        }
+        *self.try_lock_entries()? = restored;
+        Ok(())
+    }
+
+    /// Write a binary snapshot of the tracked entries with bincode
+    ///
+    /// The binary form is typically several times smaller than the JSON form of the same
+    /// [`TrackerState`] and much faster to load, but it is not human-readable and is only
+    /// readable by [`load_bincode`](Self::load_bincode) of a compatible version.
+    #[cfg(feature = "binary")]
+    pub fn save_bincode<W: Write>(&self, writer: W) -> Result<()> {
+        bincode::serialize_into(writer, &self.state())?;
+        Ok(())
+    }
+
+    /// Replace all tracked entries with a snapshot written by [`save_bincode`](Self::save_bincode)
+    #[cfg(feature = "binary")]
+    pub fn load_bincode<R: Read>(&self, reader: R) -> Result<()> {
+        let state: TrackerState = bincode::deserialize_from(reader)?;
+        self.restore_state(state)
+    }
+
+    /// Stop tracking a string, returning its entry if it was tracked
+    pub fn remove_string(&self, value: &str) -> Option<StringEntry> {
+        self.lock_entries().remove(self.entry_key(value).as_ref())
+    }
+
+    /// Release excess memory held by the tracker
+    ///
+    /// Drops entries with no recorded occurrences and shrinks the entries map and each
+    /// entry's occurrence buffer and sets to fit their contents. Useful for long-running
+    /// agents after many strings were removed or occurrence buffers were truncated.
+    pub fn compact(&self) {
+        let mut entries = self.lock_entries();
+        entries.retain(|entry| entry.total_occurrences > 0);
+        for entry in entries.values_mut() {
+            entry.occurrences.shrink_to_fit();
+            entry.unique_files.shrink_to_fit();
+            entry.unique_tools.shrink_to_fit();
+            entry.categories.shrink_to_fit();
+            entry.suspicious_indicators.shrink_to_fit();
+        }
+        entries.shrink_to_fit();
+    }
+
     /// Clear all tracked strings
     #[allow(dead_code)]
     pub fn clear(&self) {
-        let mut entries = self.entries.lock().unwrap();
+        let mut entries = self.lock_entries();
         entries.clear();
     }
 }
+
+/// Whether `token` looks like a real path rather than a switch such as `/c`: it has a
+/// drive letter, a UNC prefix or at least two separators
+fn has_path_shape(token: &str) -> bool {
+    let bytes = token.as_bytes();
+    let drive_letter = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/');
+    drive_letter || token.starts_with("\\\\") || token.matches(['/', '\\']).count() >= 2
+}
+
+/// Match `text` against a glob pattern pre-split on `*` (at least two parts)
+fn glob_match(parts: &[&str], text: &str) -> bool {
+    let (first, rest) = parts.split_first().expect("glob has parts");
+    let (last, middle) = rest.split_last().expect("glob has a wildcard");
+
+    let Some(mut remaining) = text.strip_prefix(first) else {
+        return false;
+    };
+    for part in middle {
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.ends_with(last)
+}
+
+/// Tracked entries by key, with an optional index of keys ordered by `last_seen`
+///
+/// The index lets the least recently seen entry be evicted in O(log n). It is only kept
+/// once [`track_recency`](Self::track_recency) is called, for trackers with an entry cap.
+/// Shared access derefs to the map; changes that add or remove keys or move `last_seen`
+/// must go through this type so the index stays in sync.
+#[derive(Debug, Default)]
+pub(crate) struct EntryStore {
+    map: HashMap<String, StringEntry>,
+    recency: Option<BTreeSet<(DateTime<Utc>, String)>>,
+}
+
+impl Deref for EntryStore {
+    type Target = HashMap<String, StringEntry>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl FromIterator<(String, StringEntry)> for EntryStore {
+    fn from_iter<I: IntoIterator<Item = (String, StringEntry)>>(iter: I) -> Self {
+        Self {
+            map: iter.into_iter().collect(),
+            recency: None,
+        }
+    }
+}
+
+impl EntryStore {
+    /// Start maintaining the recency index, building it from the current entries
+    fn track_recency(&mut self) {
+        if self.recency.is_none() {
+            let recency = self
+                .map
+                .iter()
+                .map(|(key, entry)| (entry.last_seen, key.clone()))
+                .collect();
+            self.recency = Some(recency);
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> Option<StringEntry> {
+        let entry = self.map.remove(key)?;
+        if let Some(recency) = &mut self.recency {
+            recency.remove(&(entry.last_seen, key.to_string()));
+        }
+        Some(entry)
+    }
+
+    /// Remove the entry with the smallest `last_seen`, ties broken by key
+    ///
+    /// Returns `None` when the recency index is not maintained.
+    fn pop_least_recent(&mut self) -> Option<StringEntry> {
+        let recency = self.recency.as_mut()?;
+        while let Some((_, key)) = recency.pop_first() {
+            if let Some(entry) = self.map.remove(&key) {
+                return Some(entry);
+            }
+        }
+        None
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&StringEntry) -> bool) {
+        let mut recency = self.recency.as_mut();
+        self.map.retain(|key, entry| {
+            let kept = keep(entry);
+            if let (false, Some(recency)) = (kept, recency.as_mut()) {
+                recency.remove(&(entry.last_seen, key.clone()));
+            }
+            kept
+        });
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        if let Some(recency) = &mut self.recency {
+            recency.clear();
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+    }
+
+    /// Mutable access to an entry; callers must not change its `last_seen`
+    fn get_mut(&mut self, key: &str) -> Option<&mut StringEntry> {
+        self.map.get_mut(key)
+    }
+
+    /// Mutable access to every entry; callers must not change `last_seen`
+    fn values_mut(&mut self) -> impl Iterator<Item = &mut StringEntry> {
+        self.map.values_mut()
+    }
+}
+
+/// Bounded collection of the `limit` greatest items, kept in a min-heap
+struct TopN<T: Ord> {
+    heap: BinaryHeap<Reverse<T>>,
+    limit: usize,
+}
+
+impl<T: Ord> TopN<T> {
+    fn new(limit: usize) -> Self {
+        Self {
+            heap: BinaryHeap::with_capacity(limit + 1),
+            limit,
+        }
+    }
+
+    fn push(&mut self, item: T) {
+        if self.heap.len() < self.limit {
+            self.heap.push(Reverse(item));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            if item > smallest.0 {
+                smallest.0 = item;
+            }
+        }
+    }
+
+    /// The retained items, greatest first
+    fn into_sorted_vec(self) -> Vec<T> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(item)| item)
+            .collect()
+    }
+}
+
+/// `f64` ordered by `total_cmp`, for use as a heap key
+struct TotalF64(f64);
+
+impl PartialEq for TotalF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TotalF64 {}
+
+impl PartialOrd for TotalF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Fraction of characters in `value` that are printable; empty strings count as clean text
+///
+/// Control characters other than `\n`, `\r` and `\t` are non-printable, as is the
+/// U+FFFD replacement character left behind by lossy decoding of binary data.
+fn printable_ratio(value: &str) -> f64 {
+    let total = value.chars().count();
+    if total == 0 {
+        return 1.0;
+    }
+
+    let printable = value
+        .chars()
+        .filter(|&c| {
+            c != char::REPLACEMENT_CHARACTER && (!c.is_control() || matches!(c, '\n' | '\r' | '\t'))
+        })
+        .count();
+    printable as f64 / total as f64
+}
+
+fn default_printable_ratio() -> f64 {
+    1.0
+}
+
+/// Decode the `-EncodedCommand` argument of a PowerShell command line, if present
+///
+/// The argument is base64 over UTF-16LE text. Returns `None` when there is no such
+/// argument or it does not decode to text.
+fn decode_powershell_command(command: &str) -> Option<String> {
+    if !POWERSHELL_REGEX.is_match(command) {
+        return None;
+    }
+
+    ENCODED_COMMAND_REGEX
+        .captures_iter(command)
+        .filter(|caps| {
+            let flag = caps[1].to_ascii_lowercase();
+            flag == "ec" || "encodedcommand".starts_with(&flag)
+        })
+        .find_map(|caps| {
+            let bytes = decode_base64(&caps[2])?;
+            if bytes.is_empty() || bytes.len() % 2 != 0 {
+                return None;
+            }
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16(&units)
+                .ok()
+                .filter(|text| !text.trim().is_empty())
+        })
+}
+
+/// Decode standard base64, ignoring trailing padding
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let input = input.trim_end_matches('=');
+    let mut decoded = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for byte in input.bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(decoded)
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+/// Levenshtein distance between two strings, or `None` if it exceeds `max`
+fn bounded_edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        if current.iter().min().is_some_and(|&m| m > max) {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    Some(previous[b.len()]).filter(|&d| d <= max)
+}
+
+/// Set of character n-grams of a string; strings shorter than `n` yield themselves
+fn char_ngrams(value: &str, n: usize) -> HashSet<&str> {
+    let boundaries: Vec<usize> = value
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(value.len()))
+        .collect();
+
+    if boundaries.len() <= n {
+        return std::iter::once(value).filter(|v| !v.is_empty()).collect();
+    }
+
+    boundaries
+        .windows(n + 1)
+        .map(|w| &value[w[0]..w[n]])
+        .collect()
+}
+
+/// FNV-1a hash over a sequence of parts, stable across runs and platforms
+fn stable_hash<'a>(parts: impl IntoIterator<Item = &'a str>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut hash = OFFSET_BASIS;
+    for part in parts {
+        // 0xff never occurs in UTF-8, so it separates parts unambiguously
+        for &byte in part.as_bytes().iter().chain(&[0xff]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
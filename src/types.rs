@@ -8,7 +8,9 @@ use std::collections::HashMap;
 pub struct AnalysisConfig {
     /// Minimum entropy threshold for suspicious detection
     pub min_suspicious_entropy: f64,
-    /// Maximum number of occurrences to track per string
+    /// Maximum number of occurrence records to store per string
+    ///
+    /// Occurrence counts are not capped, only the stored records.
     pub max_occurrences_per_string: usize,
     /// Enable time-based analysis features
     pub enable_time_analysis: bool,
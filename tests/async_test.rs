@@ -0,0 +1,90 @@
+//! Tests for the tokio-backed async tracker
+
+#![cfg(feature = "tokio")]
+
+use threatflux_string_analysis::{AsyncStringTracker, StringContext, StringFilter};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_async_tracking_from_concurrent_tasks() {
+    let tracker = AsyncStringTracker::new();
+
+    let mut handles = Vec::new();
+    for task in 0..8 {
+        let tracker = tracker.clone();
+        handles.push(tokio::spawn(async move {
+            for i in 0..25 {
+                tracker
+                    .track_string(
+                        &format!("common_value_{}", i % 5),
+                        &format!("/async/task_{}", task),
+                        &format!("hash_{}", task),
+                        "async_tool",
+                        StringContext::FileString { offset: None },
+                    )
+                    .await
+                    .unwrap();
+            }
+            tracker
+                .track_string(
+                    "cmd.exe /c whoami",
+                    &format!("/async/task_{}", task),
+                    &format!("hash_{}", task),
+                    "async_tool",
+                    StringContext::FileString { offset: None },
+                )
+                .await
+                .unwrap();
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    let stats = tracker.get_statistics(None).await;
+    assert_eq!(stats.total_unique_strings, 6);
+    assert_eq!(stats.total_occurrences, 8 * 26);
+    assert_eq!(stats.total_files_analyzed, 8);
+
+    let shared = tracker.get_string_details("common_value_0").await.unwrap();
+    assert_eq!(shared.total_occurrences, 8 * 5);
+
+    let suspicious = StringFilter {
+        suspicious_only: Some(true),
+        ..Default::default()
+    };
+    let suspicious_stats = tracker.get_statistics(Some(&suspicious)).await;
+    assert_eq!(
+        suspicious_stats.suspicious_strings,
+        vec!["cmd.exe /c whoami".to_string()]
+    );
+
+    let results = tracker.search_strings("common_value", 3).await;
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|e| e.total_occurrences == 40));
+}
+
+#[tokio::test]
+async fn test_async_invalid_filter() {
+    let tracker = AsyncStringTracker::new();
+    tracker
+        .track_string(
+            "async_value",
+            "/async/filter",
+            "filter_hash",
+            "async_tool",
+            StringContext::FileString { offset: None },
+        )
+        .await
+        .unwrap();
+
+    let invalid = StringFilter {
+        regex_pattern: Some("(unclosed".to_string()),
+        ..Default::default()
+    };
+    assert!(tracker.try_get_statistics(Some(&invalid)).await.is_err());
+
+    // The infallible variant yields empty statistics rather than admitting everything
+    let stats = tracker.get_statistics(Some(&invalid)).await;
+    assert_eq!(stats.total_unique_strings, 0);
+    assert!(stats.most_common.is_empty());
+}
@@ -0,0 +1,102 @@
+//! Tests for bincode tracker snapshots
+
+#![cfg(feature = "binary")]
+
+use threatflux_string_analysis::{StringContext, StringTracker};
+
+fn populated_tracker() -> StringTracker {
+    let tracker = StringTracker::new();
+    for i in 0..200 {
+        let value = match i % 4 {
+            0 => format!("http://c2-{}.example.net/gate.php", i),
+            1 => format!("C:\\Windows\\Temp\\drop_{}.exe", i),
+            2 => format!("cmd.exe /c del payload_{}", i),
+            _ => format!("benign_value_{}", i),
+        };
+        for file in 0..3 {
+            tracker
+                .track_string(
+                    &value,
+                    &format!("/samples/file_{}", file),
+                    &format!("hash_{}", file),
+                    "snapshot_tool",
+                    StringContext::FileString {
+                        offset: Some(i * 16),
+                    },
+                )
+                .unwrap();
+        }
+    }
+    tracker
+}
+
+#[test]
+fn test_bincode_round_trip() {
+    let tracker = populated_tracker();
+    let mut buffer = Vec::new();
+    tracker.save_bincode(&mut buffer).unwrap();
+
+    let restored = StringTracker::new();
+    restored.load_bincode(buffer.as_slice()).unwrap();
+
+    let original_stats = tracker.get_statistics(None);
+    let restored_stats = restored.get_statistics(None);
+    assert_eq!(
+        restored_stats.total_unique_strings,
+        original_stats.total_unique_strings
+    );
+    assert_eq!(
+        restored_stats.total_occurrences,
+        original_stats.total_occurrences
+    );
+    assert_eq!(
+        restored_stats.total_files_analyzed,
+        original_stats.total_files_analyzed
+    );
+    assert_eq!(restored_stats.most_common, original_stats.most_common);
+    assert_eq!(
+        restored_stats.suspicious_strings,
+        original_stats.suspicious_strings
+    );
+    assert_eq!(
+        restored_stats.high_entropy_strings,
+        original_stats.high_entropy_strings
+    );
+    assert_eq!(
+        restored_stats.category_distribution,
+        original_stats.category_distribution
+    );
+
+    let original = tracker
+        .get_string_details("cmd.exe /c del payload_2")
+        .unwrap();
+    let reloaded = restored
+        .get_string_details("cmd.exe /c del payload_2")
+        .unwrap();
+    assert_eq!(reloaded.first_seen, original.first_seen);
+    assert_eq!(reloaded.occurrences.len(), original.occurrences.len());
+    assert_eq!(
+        reloaded.suspicious_indicators.len(),
+        original.suspicious_indicators.len()
+    );
+
+    // Corrupt input is reported rather than partially loaded
+    assert!(restored.load_bincode(&buffer[..buffer.len() / 2]).is_err());
+    assert_eq!(restored.get_statistics(None).total_unique_strings, 200);
+}
+
+#[test]
+fn test_bincode_smaller_than_json() {
+    let tracker = populated_tracker();
+
+    let mut binary = Vec::new();
+    tracker.save_bincode(&mut binary).unwrap();
+    let json = serde_json::to_vec(&tracker.state()).unwrap();
+
+    assert!(
+        binary.len() < json.len(),
+        "bincode {} bytes vs JSON {} bytes",
+        binary.len(),
+        json.len()
+    );
+}
